@@ -0,0 +1,391 @@
+mod error;
+
+pub use self::error::{AssemblyError, AssemblyErrorKind};
+use crate::{
+    Integer, Value,
+    config::{BYTECODE_MAGIC, BYTECODE_VERSION, INTEGER_BASE},
+    cons::Tag,
+    instruction::Instruction,
+    value::number_from_i64,
+};
+
+/// Assembles a program into bytecode for a machine of a value type `V` and a
+/// code register `C`.
+///
+/// Each line of a source has an instruction name, a register address, and an
+/// operand separated by whitespaces. The `operate` instruction has no register
+/// address and the `return` instruction has no operand. The operands of the
+/// `if` and `call` instructions are numbers of instructions they skip to reach
+/// their targets. Comments start with `;`.
+///
+/// ```text
+/// ; Pushes 42 onto the register at `0b11`.
+/// cons 0b11 42
+/// ```
+pub fn assemble<V: Value, const C: usize>(
+    source: &str,
+    bytecode: &mut impl Extend<u8>,
+) -> Result<(), AssemblyError> {
+    let mut count = 0;
+    let mut length = Length::default();
+
+    for line in source.lines() {
+        count += 1;
+
+        if let Some((tag, operand)) = parse_line::<V>(count, line)? {
+            encode_instruction(&mut length, tag, operand);
+        }
+    }
+
+    bytecode.extend(BYTECODE_MAGIC);
+    bytecode.extend([BYTECODE_VERSION, V::Number::BITS as _]);
+    encode_integer(bytecode, C as _);
+    encode_integer(bytecode, length.0 as _);
+
+    for (index, line) in source.lines().rev().enumerate() {
+        if let Some((tag, operand)) = parse_line::<V>(count - index, line)? {
+            encode_instruction(bytecode, tag, operand);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct Length(usize);
+
+impl Extend<u8> for Length {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, bytes: I) {
+        self.0 += bytes.into_iter().count();
+    }
+}
+
+fn parse_line<V: Value>(line: usize, source: &str) -> Result<Option<(Tag, i64)>, AssemblyError> {
+    let source = source.split(';').next().unwrap_or_default();
+    let error = |column, kind| AssemblyError::new(line, column, kind);
+    let end = source.chars().count() + 1;
+    let mut tokens = tokenize(source);
+
+    let Some((column, name)) = tokens.next() else {
+        return Ok(None);
+    };
+    let instruction = Instruction::from_name(name)
+        .ok_or_else(|| error(column, AssemblyErrorKind::UnknownInstruction))?;
+
+    let register = if instruction.has_register() {
+        let (column, register) = tokens
+            .next()
+            .ok_or_else(|| error(end, AssemblyErrorKind::MissingRegister))?;
+        let register = parse_integer(register)
+            .ok_or_else(|| error(column, AssemblyErrorKind::InvalidInteger))?;
+
+        if !(0..Instruction::REGISTERS as i64).contains(&register) {
+            return Err(error(column, AssemblyErrorKind::InvalidRegister));
+        }
+
+        register as _
+    } else {
+        0
+    };
+
+    let operand = if instruction.has_operand() {
+        let (column, operand) = tokens
+            .next()
+            .ok_or_else(|| error(end, AssemblyErrorKind::MissingOperand))?;
+
+        parse_integer(operand)
+            .filter(|&operand| number_from_i64::<V>(operand).is_some())
+            .ok_or_else(|| error(column, AssemblyErrorKind::InvalidInteger))?
+    } else {
+        0
+    };
+
+    if let Some((column, _)) = tokens.next() {
+        return Err(error(column, AssemblyErrorKind::UnexpectedToken));
+    }
+
+    Ok(Some((instruction.tag(register), operand)))
+}
+
+fn tokenize(source: &str) -> impl Iterator<Item = (usize, &str)> {
+    source.split_whitespace().map(move |token| {
+        (
+            source[..token.as_ptr().addr() - source.as_ptr().addr()]
+                .chars()
+                .count()
+                + 1,
+            token,
+        )
+    })
+}
+
+fn parse_integer(token: &str) -> Option<i64> {
+    let (sign, token) = token
+        .strip_prefix('-')
+        .map_or((1, token), |token| (-1, token));
+    let (radix, digits) = match (token.strip_prefix("0b"), token.strip_prefix("0x")) {
+        (Some(digits), _) => (2, digits),
+        (_, Some(digits)) => (16, digits),
+        _ => (10, token),
+    };
+
+    if digits.starts_with(['+', '-']) {
+        return None;
+    }
+
+    i64::from_str_radix(digits, radix).ok()?.checked_mul(sign)
+}
+
+fn encode_instruction(bytecode: &mut impl Extend<u8>, tag: Tag, operand: i64) {
+    bytecode.extend([tag]);
+    encode_integer(
+        bytecode,
+        ((operand << 1) ^ (operand >> (i64::BITS - 1))) as _,
+    );
+}
+
+fn encode_integer(bytecode: &mut impl Extend<u8>, mut integer: u64) {
+    loop {
+        let byte = (integer % INTEGER_BASE) as u8;
+        integer /= INTEGER_BASE;
+
+        bytecode.extend([byte << 1 | (integer != 0) as u8]);
+
+        if integer == 0 {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Value16, Value64};
+    use alloc::{format, vec::Vec};
+    use pretty_assertions::assert_eq;
+
+    const HEADER_SIZE: usize = 8;
+
+    fn assemble_bytes(source: &str) -> Result<Vec<u8>, AssemblyError> {
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(source, &mut bytecode)?;
+
+        Ok(bytecode[HEADER_SIZE..].into())
+    }
+
+    #[test]
+    fn assemble_empty_source() {
+        assert_eq!(assemble_bytes(""), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn assemble_header() {
+        let mut bytecode = Vec::new();
+
+        assemble::<Value16, 0b1010>("cons 0b11 42\ncons 0b11 100", &mut bytecode).unwrap();
+
+        assert_eq!(
+            bytecode[..HEADER_SIZE],
+            [
+                b'm',
+                b'o',
+                b'c',
+                b'o',
+                BYTECODE_VERSION,
+                16,
+                0b10100,
+                0b1010
+            ]
+        );
+        assert_eq!(bytecode.len(), HEADER_SIZE + 5);
+    }
+
+    #[test]
+    fn assemble_cons() {
+        assert_eq!(assemble_bytes("cons 0b11 42"), Ok([0b11000, 168].into()));
+    }
+
+    #[test]
+    fn assemble_move() {
+        assert_eq!(assemble_bytes("move 3 0"), Ok([0b11001, 0].into()));
+    }
+
+    #[test]
+    fn assemble_operate() {
+        assert_eq!(assemble_bytes("operate 5"), Ok([0b10, 20].into()));
+    }
+
+    #[test]
+    fn assemble_if() {
+        assert_eq!(assemble_bytes("if 0b11 2"), Ok([0b11011, 8].into()));
+    }
+
+    #[test]
+    fn assemble_call() {
+        assert_eq!(assemble_bytes("call 0b11 1"), Ok([0b11100, 4].into()));
+    }
+
+    #[test]
+    fn assemble_return() {
+        assert_eq!(assemble_bytes("return 0b11"), Ok([0b11101, 0].into()));
+    }
+
+    #[test]
+    fn assemble_instructions_in_reverse_order() {
+        assert_eq!(
+            assemble_bytes("cons 0b11 1\ncons 0b11 2"),
+            Ok([0b11000, 8, 0b11000, 4].into())
+        );
+    }
+
+    #[test]
+    fn assemble_negative_operand() {
+        assert_eq!(
+            assemble_bytes("cons 0b11 -1\ncons 0b11 -2"),
+            Ok([0b11000, 6, 0b11000, 2].into())
+        );
+    }
+
+    #[test]
+    fn assemble_multi_byte_operand() {
+        assert_eq!(
+            assemble_bytes("cons 0b11 100"),
+            Ok([0b11000, 145, 2].into())
+        );
+    }
+
+    #[test]
+    fn assemble_hexadecimal_operand() {
+        assert_eq!(assemble_bytes("cons 0b11 0x2a"), Ok([0b11000, 168].into()));
+    }
+
+    #[test]
+    fn skip_comments_and_blank_lines() {
+        assert_eq!(
+            assemble_bytes("; comment\n\n  cons 0b11 42 ; comment\n"),
+            Ok([0b11000, 168].into())
+        );
+    }
+
+    #[test]
+    fn fail_to_assemble_unknown_instruction() {
+        assert_eq!(
+            assemble_bytes("cons 0b11 42\n  foo 0b11 42"),
+            Err(AssemblyError::new(
+                2,
+                3,
+                AssemblyErrorKind::UnknownInstruction
+            ))
+        );
+    }
+
+    #[test]
+    fn fail_to_assemble_missing_register() {
+        assert_eq!(
+            assemble_bytes("cons"),
+            Err(AssemblyError::new(1, 5, AssemblyErrorKind::MissingRegister))
+        );
+    }
+
+    #[test]
+    fn fail_to_assemble_missing_operand() {
+        assert_eq!(
+            assemble_bytes("cons 0b11 ; comment"),
+            Err(AssemblyError::new(1, 11, AssemblyErrorKind::MissingOperand))
+        );
+    }
+
+    #[test]
+    fn fail_to_assemble_invalid_integer() {
+        assert_eq!(
+            assemble_bytes("cons 0b11 4x2"),
+            Err(AssemblyError::new(1, 11, AssemblyErrorKind::InvalidInteger))
+        );
+    }
+
+    #[test]
+    fn fail_to_assemble_out_of_range_operand() {
+        let mut bytecode = Vec::new();
+
+        assemble::<Value16, 0b10>("cons 0b11 8191\ncons 0b11 -8192", &mut bytecode).unwrap();
+
+        for operand in ["8192", "-8193"] {
+            assert_eq!(
+                assemble::<Value16, 0b10>(&format!("cons 0b11 {operand}"), &mut bytecode),
+                Err(AssemblyError::new(1, 11, AssemblyErrorKind::InvalidInteger))
+            );
+        }
+    }
+
+    #[test]
+    fn fail_to_assemble_invalid_register() {
+        assert_eq!(
+            assemble_bytes("cons 32 42"),
+            Err(AssemblyError::new(1, 6, AssemblyErrorKind::InvalidRegister))
+        );
+        assert_eq!(
+            assemble_bytes("cons -1 42"),
+            Err(AssemblyError::new(1, 6, AssemblyErrorKind::InvalidRegister))
+        );
+    }
+
+    #[test]
+    fn fail_to_assemble_operate_with_register() {
+        assert_eq!(
+            assemble_bytes("operate 0b11 5"),
+            Err(AssemblyError::new(
+                1,
+                14,
+                AssemblyErrorKind::UnexpectedToken
+            ))
+        );
+    }
+
+    #[test]
+    fn fail_to_assemble_return_with_operand() {
+        assert_eq!(
+            assemble_bytes("return 0b11 0"),
+            Err(AssemblyError::new(
+                1,
+                13,
+                AssemblyErrorKind::UnexpectedToken
+            ))
+        );
+    }
+
+    #[test]
+    fn fail_to_assemble_unexpected_token() {
+        assert_eq!(
+            assemble_bytes("cons 0b11 42 43"),
+            Err(AssemblyError::new(
+                1,
+                14,
+                AssemblyErrorKind::UnexpectedToken
+            ))
+        );
+    }
+
+    #[test]
+    fn report_first_error() {
+        assert_eq!(
+            assemble_bytes("foo\nbar"),
+            Err(AssemblyError::new(
+                1,
+                1,
+                AssemblyErrorKind::UnknownInstruction
+            ))
+        );
+    }
+
+    #[test]
+    fn display_error() {
+        assert_eq!(
+            alloc::format!(
+                "{}",
+                AssemblyError::new(1, 2, AssemblyErrorKind::UnknownInstruction)
+            ),
+            "1:2: unknown instruction"
+        );
+    }
+}
@@ -0,0 +1,71 @@
+use core::{
+    error,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+/// An assembly error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct AssemblyError {
+    line: usize,
+    column: usize,
+    kind: AssemblyErrorKind,
+}
+
+impl AssemblyError {
+    pub(crate) const fn new(line: usize, column: usize, kind: AssemblyErrorKind) -> Self {
+        Self { line, column, kind }
+    }
+
+    /// Returns a line number.
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns a column number.
+    pub const fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns an error kind.
+    pub const fn kind(&self) -> AssemblyErrorKind {
+        self.kind
+    }
+}
+
+impl error::Error for AssemblyError {}
+
+impl Display for AssemblyError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "{}:{}: {}", self.line, self.column, self.kind)
+    }
+}
+
+/// An assembly error kind.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AssemblyErrorKind {
+    /// Invalid integer.
+    InvalidInteger,
+    /// Invalid register.
+    InvalidRegister,
+    /// Missing operand.
+    MissingOperand,
+    /// Missing register.
+    MissingRegister,
+    /// Unexpected token.
+    UnexpectedToken,
+    /// Unknown instruction.
+    UnknownInstruction,
+}
+
+impl Display for AssemblyErrorKind {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidInteger => write!(formatter, "invalid integer"),
+            Self::InvalidRegister => write!(formatter, "invalid register"),
+            Self::MissingOperand => write!(formatter, "missing operand"),
+            Self::MissingRegister => write!(formatter, "missing register"),
+            Self::UnexpectedToken => write!(formatter, "unexpected token"),
+            Self::UnknownInstruction => write!(formatter, "unknown instruction"),
+        }
+    }
+}
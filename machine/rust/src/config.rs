@@ -1 +1,14 @@
 pub const INTEGER_BASE: u64 = 1 << 7;
+pub const BYTECODE_MAGIC: [u8; 4] = *b"moco";
+// A bytecode format version is bumped whenever instructions change their
+// meanings or encodings.
+//
+// - 1: initial format
+// - 2: operands of `move` instructions are register addresses
+// - 3: opcodes are widened to 2 bits for `operate` instructions
+// - 4: opcodes are widened to 3 bits for `call` and `return` instructions and
+//   registers are narrowed to 5 bits
+pub const BYTECODE_VERSION: u8 = 4;
+pub const HANDLE_COUNT: usize = 32;
+#[cfg(feature = "trace")]
+pub const TRACE_LENGTH: usize = 8;
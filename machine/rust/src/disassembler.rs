@@ -0,0 +1,340 @@
+mod error;
+
+pub use self::error::DisassemblyError;
+use crate::{Heap, Machine, Memory, Value, VoidOperationSet, instruction::Instruction};
+use core::fmt::Write;
+
+/// Disassembles a code list in a memory.
+///
+/// Each line of a listing has an instruction name, a register address, an
+/// operand, and a register path in a comment. The `operate` instruction has no
+/// register address and the `return` instruction has no operand. The operands
+/// of the `if` and `call` instructions are numbers of instructions they skip,
+/// which are negative for backward branches. The listing can be assembled
+/// again by [`assemble`](crate::assemble).
+pub fn disassemble<V: Value, H: Heap<V>>(
+    memory: &Memory<V, H>,
+    head: V,
+    writer: &mut impl Write,
+) -> Result<(), DisassemblyError> {
+    let mut code = head;
+    let mut position = 0;
+
+    while let Ok(cons) = code.to_cons()
+        && let Ok(instruction) = memory.get(cons.index() + 1)?.to_cons()
+    {
+        if position > memory.size() / 2 {
+            return Err(DisassemblyError::CyclicCodeList);
+        }
+
+        let tag = instruction.tag();
+        let address = Instruction::register(tag);
+        let operation = Instruction::from_tag(tag);
+        let register = operation.is_none_or(Instruction::has_register);
+
+        write!(writer, "{}", operation.map_or("unknown", Instruction::name))?;
+
+        if register {
+            write!(writer, " {address:#b}")?;
+        }
+
+        if operation.is_none_or(Instruction::has_operand) {
+            let operand = memory.get(cons.index())?;
+
+            write!(writer, " ")?;
+
+            if operation.is_some_and(Instruction::has_branch)
+                && let Some(count) = branch_count(memory, head, position, operand)?
+            {
+                write!(writer, "{count}")?;
+            } else {
+                write_operand(writer, operand)?;
+            }
+        }
+
+        if register {
+            write!(writer, " ; ")?;
+            write_path(writer, address)?;
+        }
+
+        writeln!(writer)?;
+
+        code = instruction.into();
+        position += 1;
+    }
+
+    Ok(())
+}
+
+/// Disassembles bytecode for a machine of a value type `V` and a code register
+/// `C`.
+///
+/// Bytecode is decoded into a heap before disassembly so that instructions
+/// are listed in execution order.
+pub fn disassemble_bytecode<V: Value, H: Heap<V>, const C: usize>(
+    heap: H,
+    bytecode: impl IntoIterator<Item = u8>,
+    writer: &mut impl Write,
+) -> Result<(), DisassemblyError> {
+    let mut machine = Machine::<V, H, C, _>::new(heap, VoidOperationSet::new())?;
+
+    machine.initialize(bytecode)?;
+
+    disassemble(machine.memory(), machine.code()?, writer)
+}
+
+// Returns a number of instructions a branch at a position skips by searching
+// its target from the head of a code list.
+fn branch_count<V: Value, H: Heap<V>>(
+    memory: &Memory<V, H>,
+    mut code: V,
+    position: usize,
+    operand: V,
+) -> Result<Option<isize>, DisassemblyError> {
+    let Ok(target) = operand.to_cons() else {
+        return Ok(None);
+    };
+    let mut index = 0;
+
+    while let Ok(cons) = code.to_cons()
+        && index <= memory.size() / 2
+    {
+        if cons.index() == target.index() {
+            return Ok(Some(index as isize - position as isize - 1));
+        }
+
+        code = memory.get(cons.index() + 1)?;
+        index += 1;
+    }
+
+    Ok(None)
+}
+
+fn write_operand<V: Value>(writer: &mut impl Write, operand: V) -> Result<(), DisassemblyError> {
+    match operand.to_number() {
+        Ok(number) => write!(writer, "{number}")?,
+        Err(cons) => write!(writer, "#{}", cons.index())?,
+    }
+
+    Ok(())
+}
+
+fn write_path(writer: &mut impl Write, address: usize) -> Result<(), DisassemblyError> {
+    if address < 2 {
+        write!(writer, "invalid")?;
+        return Ok(());
+    }
+
+    write!(writer, "c")?;
+
+    for bit in (0..address.ilog2()).rev() {
+        write!(
+            writer,
+            "{}",
+            if address >> bit & 1 == 0 { "a" } else { "d" }
+        )?;
+    }
+
+    write!(writer, "r")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Value64, assemble, config::BYTECODE_VERSION};
+    use alloc::{string::String, vec::Vec};
+    use pretty_assertions::assert_eq;
+
+    const HEAP_SIZE: usize = 1 << 8;
+
+    fn disassemble_source(source: &str) -> String {
+        let mut bytecode = Vec::new();
+        let mut listing = String::new();
+
+        assemble::<Value64, 0b10>(source, &mut bytecode).unwrap();
+        disassemble_bytecode::<Value64, _, 0b10>(
+            [Default::default(); HEAP_SIZE],
+            bytecode,
+            &mut listing,
+        )
+        .unwrap();
+
+        listing
+    }
+
+    #[test]
+    fn disassemble_empty_bytecode() {
+        assert_eq!(disassemble_source(""), "");
+    }
+
+    #[test]
+    fn disassemble_instructions() {
+        assert_eq!(
+            disassemble_source("cons 0b11 42\nmove 0b110 -1\ncons 0b101 0\noperate 3"),
+            "cons 0b11 42 ; cdr\nmove 0b110 -1 ; cdar\ncons 0b101 0 ; cadr\noperate 3\n"
+        );
+    }
+
+    #[test]
+    fn disassemble_branch() {
+        assert_eq!(
+            disassemble_source("if 0b11 1\ncons 0b11 1\ncall 0b101 0\nreturn 0b101"),
+            "if 0b11 1 ; cdr\ncons 0b11 1 ; cdr\ncall 0b101 0 ; cadr\nreturn 0b101 ; cadr\n"
+        );
+    }
+
+    #[test]
+    fn disassemble_invalid_path() {
+        assert_eq!(disassemble_source("cons 1 0"), "cons 0b1 0 ; invalid\n");
+        assert_eq!(disassemble_source("cons 0 0"), "cons 0b0 0 ; invalid\n");
+    }
+
+    #[test]
+    fn assemble_listing() {
+        let source =
+            "cons 0b11 42\nmove 0b1010 7\nif 0b11 1\ncall 0b11 0\noperate 3\nreturn 0b11\n";
+        let listing = disassemble_source(source);
+        let mut bytecode = Vec::new();
+        let mut other_bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(source, &mut bytecode).unwrap();
+        assemble::<Value64, 0b10>(&listing, &mut other_bytecode).unwrap();
+
+        assert_eq!(bytecode, other_bytecode);
+    }
+
+    #[test]
+    fn assemble_listing_with_backward_branches() {
+        let source =
+            "cons 0b11 1\nif 0b11 -2\ncall 0b101 -3\nif 0b11 -1\ncall 0b101 1\nreturn 0b101\n";
+        let listing = disassemble_source(source);
+        let mut bytecode = Vec::new();
+        let mut other_bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(source, &mut bytecode).unwrap();
+        assemble::<Value64, 0b10>(&listing, &mut other_bytecode).unwrap();
+
+        assert_eq!(
+            listing,
+            "cons 0b11 1 ; cdr\nif 0b11 -2 ; cdr\ncall 0b101 -3 ; cadr\nif 0b11 -1 ; cdr\ncall 0b101 1 ; cadr\nreturn 0b101 ; cadr\n"
+        );
+        assert_eq!(bytecode, other_bytecode);
+    }
+
+    #[test]
+    fn fail_to_disassemble_cyclic_code_list() {
+        let mut memory =
+            Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
+        let cons = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(cons.into());
+        memory
+            .set(
+                cons.index() + 1,
+                cons.set_tag(Instruction::Cons.tag(0b11)).into(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            disassemble(&memory, cons.into(), &mut String::new()),
+            Err(DisassemblyError::CyclicCodeList)
+        );
+    }
+
+    #[test]
+    fn assemble_listing_of_every_instruction() {
+        let mut source = String::new();
+
+        for &instruction in Instruction::ALL {
+            let registers = if instruction.has_register() {
+                0..Instruction::REGISTERS
+            } else {
+                0..1
+            };
+
+            for register in registers {
+                source += instruction.name();
+
+                if instruction.has_register() {
+                    source += &alloc::format!(" {register}");
+                }
+
+                if instruction.has_operand() {
+                    source += " 0";
+                }
+
+                source += "\n";
+            }
+        }
+
+        let mut bytecode = Vec::new();
+        let mut listing = String::new();
+        let mut other_bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(&source, &mut bytecode).unwrap();
+        disassemble_bytecode::<Value64, _, 0b10>(
+            [Default::default(); 1 << 10],
+            bytecode.iter().copied(),
+            &mut listing,
+        )
+        .unwrap();
+        assemble::<Value64, 0b10>(&listing, &mut other_bytecode).unwrap();
+
+        assert_eq!(listing.lines().count(), source.lines().count());
+        assert_eq!(bytecode, other_bytecode);
+    }
+
+    #[test]
+    fn disassemble_memory() {
+        let mut memory =
+            Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
+        let cons = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(cons.into());
+        let cons = memory
+            .allocate(
+                Default::default(),
+                cons.set_tag(Instruction::Cons.tag(0b11)).into(),
+            )
+            .unwrap();
+        memory.set_root(cons.into());
+        let operand = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set(cons.index(), operand.into()).unwrap();
+        let mut listing = String::new();
+
+        disassemble(&memory, cons.into(), &mut listing).unwrap();
+
+        assert_eq!(
+            listing,
+            alloc::format!("cons 0b11 #{} ; cdr\n", operand.index())
+        );
+    }
+
+    #[test]
+    fn fail_to_disassemble_truncated_bytecode() {
+        assert_eq!(
+            disassemble_bytecode::<Value64, _, 0b10>(
+                [Default::default(); HEAP_SIZE],
+                [
+                    b'm',
+                    b'o',
+                    b'c',
+                    b'o',
+                    BYTECODE_VERSION,
+                    64,
+                    0b100,
+                    0b10,
+                    0b110
+                ],
+                &mut String::new(),
+            ),
+            Err(DisassemblyError::Machine(crate::Error::BytecodeEnd))
+        );
+    }
+}
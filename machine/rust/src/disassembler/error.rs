@@ -0,0 +1,47 @@
+use crate::Error;
+use core::{
+    error,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+/// A disassembly error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DisassemblyError {
+    /// A cyclic code list.
+    CyclicCodeList,
+    /// A format error.
+    Format,
+    /// A machine error.
+    Machine(Error),
+}
+
+impl error::Error for DisassemblyError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Machine(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for DisassemblyError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::CyclicCodeList => write!(formatter, "cyclic code list"),
+            Self::Format => write!(formatter, "format error"),
+            Self::Machine(_) => write!(formatter, "machine error"),
+        }
+    }
+}
+
+impl From<fmt::Error> for DisassemblyError {
+    fn from(_: fmt::Error) -> Self {
+        Self::Format
+    }
+}
+
+impl From<Error> for DisassemblyError {
+    fn from(error: Error) -> Self {
+        Self::Machine(error)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::Violation;
 use core::{
     error,
     fmt::{self, Debug, Display, Formatter},
@@ -8,12 +9,36 @@ use core::{
 pub enum Error {
     /// Unexpected end of bytecode.
     BytecodeEnd,
+    /// Code register mismatch.
+    CodeRegisterMismatch,
+    /// Cons expected.
+    ConsExpected,
+    /// Too many handles in a scope.
+    HandleLimit,
+    /// Invalid branch.
+    InvalidBranch,
+    /// Invalid bytecode magic number.
+    InvalidMagic,
     /// Invalid memory access.
     InvalidMemoryAccess,
+    /// Invalid operand.
+    InvalidOperand,
+    /// Invalid program.
+    InvalidProgram(Violation),
+    /// Invalid register.
+    InvalidRegister,
     /// Number expected.
     NumberExpected,
     /// Out of memory.
     OutOfMemory,
+    /// Trailing bytecode.
+    TrailingBytecode,
+    /// Unknown instruction.
+    UnknownInstruction,
+    /// Unsupported bytecode version.
+    UnsupportedVersion,
+    /// Value width mismatch.
+    ValueWidthMismatch,
 }
 
 impl error::Error for Error {}
@@ -22,9 +47,92 @@ impl Display for Error {
     fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
         match self {
             Self::BytecodeEnd => write!(formatter, "unexpected end of bytecode"),
+            Self::CodeRegisterMismatch => write!(formatter, "code register mismatch"),
+            Self::ConsExpected => write!(formatter, "cons expected"),
+            Self::HandleLimit => write!(formatter, "too many handles"),
+            Self::InvalidBranch => write!(formatter, "invalid branch"),
+            Self::InvalidMagic => write!(formatter, "invalid bytecode magic number"),
             Self::InvalidMemoryAccess => write!(formatter, "invalid memory access"),
+            Self::InvalidOperand => write!(formatter, "invalid operand"),
+            Self::InvalidProgram(violation) => write!(formatter, "invalid program: {violation}"),
+            Self::InvalidRegister => write!(formatter, "invalid register"),
             Self::NumberExpected => write!(formatter, "number expected"),
             Self::OutOfMemory => write!(formatter, "out of memory"),
+            Self::TrailingBytecode => write!(formatter, "trailing bytecode"),
+            Self::UnknownInstruction => write!(formatter, "unknown instruction"),
+            Self::UnsupportedVersion => write!(formatter, "unsupported bytecode version"),
+            Self::ValueWidthMismatch => write!(formatter, "value width mismatch"),
         }
     }
 }
+
+/// A run error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RunError<E> {
+    /// A machine error.
+    Machine(Error),
+    /// An operation error.
+    Operation(E),
+}
+
+impl<E: error::Error + 'static> error::Error for RunError<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Machine(error) => Some(error),
+            Self::Operation(error) => Some(error),
+        }
+    }
+}
+
+impl<E> Display for RunError<E> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Machine(_) => write!(formatter, "machine error"),
+            Self::Operation(_) => write!(formatter, "operation error"),
+        }
+    }
+}
+
+impl<E> From<Error> for RunError<E> {
+    fn from(error: Error) -> Self {
+        Self::Machine(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ArithmeticError, VoidError};
+    use alloc::string::ToString;
+    use core::error::Error as _;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn chain_operation_error() {
+        let error = RunError::Operation(ArithmeticError::Machine(Error::NumberExpected));
+        let source = error.source().unwrap();
+
+        assert_eq!(error.to_string(), "operation error");
+        assert_eq!(
+            source.downcast_ref(),
+            Some(&ArithmeticError::Machine(Error::NumberExpected))
+        );
+        assert_eq!(source.to_string(), "machine error");
+        assert_eq!(
+            source.source().unwrap().downcast_ref(),
+            Some(&Error::NumberExpected)
+        );
+        assert!(source.source().unwrap().source().is_none());
+    }
+
+    #[test]
+    fn chain_machine_error() {
+        let error = RunError::<VoidError>::Machine(Error::OutOfMemory);
+
+        assert_eq!(error.to_string(), "machine error");
+        assert_eq!(
+            error.source().unwrap().downcast_ref(),
+            Some(&Error::OutOfMemory)
+        );
+    }
+}
@@ -0,0 +1,448 @@
+use crate::{Error, Heap, Integer, Memory, Value, config::HANDLE_COUNT, cons::Tag};
+use core::{
+    cell::RefCell,
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+};
+
+// An invariant lifetime that brands a scope and its handles.
+type Brand<'id> = PhantomData<fn(&'id ()) -> &'id ()>;
+
+/// A scope of handles.
+///
+/// A scope borrows a memory exclusively and hands out handles to values in
+/// it. Each handle occupies a slot of its scope, which is a root of garbage
+/// collection until the handle is dropped. A scope has a fixed number of
+/// slots, and slots of dropped handles are reused. Creating a handle returns
+/// a handle limit error if all the slots are in use.
+///
+/// A scope and its handles are branded with a unique lifetime of a closure
+/// they are passed to. So they cannot escape the closure, and handles of
+/// another scope are rejected at compile time.
+///
+/// ```compile_fail
+/// use moco_machine::{Memory, Scope, Value64};
+///
+/// let mut one = Memory::<Value64, [Value64; 64]>::new([Default::default(); _]).unwrap();
+/// let mut other = Memory::<Value64, [Value64; 64]>::new([Default::default(); _]).unwrap();
+///
+/// Scope::enter(&mut one, |one| {
+///     Scope::enter(&mut other, |other| {
+///         let x = one.number(1).unwrap();
+///
+///         other.cons(&x, &x).unwrap();
+///     })
+/// });
+/// ```
+pub struct Scope<'id, V: Value, H: Heap<V>> {
+    memory: RefCell<&'id mut Memory<V, H>>,
+    slots: RefCell<Slots<V>>,
+    brand: Brand<'id>,
+}
+
+impl<'id, V: Value, H: Heap<V>> Scope<'id, V, H> {
+    /// Runs a closure with a scope.
+    pub fn enter<T>(
+        memory: &mut Memory<V, H>,
+        run: impl for<'a> FnOnce(&'a Scope<'a, V, H>) -> T,
+    ) -> T {
+        run(&Scope {
+            memory: RefCell::new(memory),
+            slots: RefCell::new(Slots::new()),
+            brand: PhantomData,
+        })
+    }
+
+    /// Creates a handle of a number.
+    pub fn number(&'id self, number: V::Number) -> Result<Handle<'id, V, H>, Error> {
+        self.handle(number.into())
+    }
+
+    /// Allocates a cons and creates a handle of it.
+    pub fn cons(
+        &'id self,
+        car: &Handle<'id, V, H>,
+        cdr: &Handle<'id, V, H>,
+    ) -> Result<Handle<'id, V, H>, Error> {
+        let cons = {
+            let mut slots = self.slots.borrow_mut();
+            let (car, cdr) = (slots.get(car.slot), slots.get(cdr.slot));
+
+            self.memory
+                .borrow_mut()
+                .allocate_with(car, cdr, slots.values_mut())?
+        };
+
+        self.handle(cons.into())
+    }
+
+    /// Creates a handle of a value in a register.
+    pub fn register(&'id self, address: usize) -> Result<Handle<'id, V, H>, Error> {
+        let value = {
+            let memory = self.memory.borrow();
+            memory.get(memory.resolve(address)?)?
+        };
+
+        self.handle(value)
+    }
+
+    /// Sets a value of a handle in a register.
+    pub fn set_register(
+        &'id self,
+        address: usize,
+        handle: &Handle<'id, V, H>,
+    ) -> Result<(), Error> {
+        let value = handle.value();
+        let mut memory = self.memory.borrow_mut();
+        let index = memory.resolve(address)?;
+
+        memory.set(index, value)
+    }
+
+    fn handle(&'id self, value: V) -> Result<Handle<'id, V, H>, Error> {
+        Ok(Handle {
+            scope: self,
+            slot: self.slots.borrow_mut().insert(value)?,
+        })
+    }
+}
+
+impl<V: Value, H: Heap<V>> Debug for Scope<'_, V, H> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.debug_struct("Scope").finish_non_exhaustive()
+    }
+}
+
+// Slots of handles.
+//
+// Free slots are chained by indices of next free slots stored as numbers in
+// them. An index is offset by one so that zero ends the chain.
+struct Slots<V> {
+    values: [V; HANDLE_COUNT],
+    length: usize,
+    free: V,
+}
+
+impl<V: Value> Slots<V> {
+    fn new() -> Self {
+        Self {
+            values: [Default::default(); _],
+            length: 0,
+            free: Default::default(),
+        }
+    }
+
+    const fn get(&self, slot: usize) -> V {
+        self.values[slot]
+    }
+
+    fn values_mut(&mut self) -> &mut [V] {
+        &mut self.values[..self.length]
+    }
+
+    fn insert(&mut self, value: V) -> Result<usize, Error> {
+        let slot = match self.free.to_number() {
+            Ok(number) if number != Default::default() => {
+                let slot = number.to_usize() - 1;
+                self.free = self.values[slot];
+                slot
+            }
+            _ if self.length < HANDLE_COUNT => {
+                self.length += 1;
+                self.length - 1
+            }
+            _ => return Err(Error::HandleLimit),
+        };
+
+        self.values[slot] = value;
+
+        Ok(slot)
+    }
+
+    fn remove(&mut self, slot: usize) {
+        self.values[slot] = self.free;
+        self.free = V::Number::from_usize(slot + 1).into();
+    }
+}
+
+/// A handle of a value in a memory.
+///
+/// A handle keeps its value alive during garbage collection. It reads its
+/// value from its slot on every access so that the value is never stale.
+pub struct Handle<'id, V: Value, H: Heap<V>> {
+    scope: &'id Scope<'id, V, H>,
+    slot: usize,
+}
+
+impl<'id, V: Value, H: Heap<V>> Handle<'id, V, H> {
+    /// Converts a value into a number.
+    pub fn to_number(&self) -> Result<V::Number, Error> {
+        self.value().to_number().map_err(|_| Error::NumberExpected)
+    }
+
+    /// Returns `true` if a value is a cons.
+    pub fn is_cons(&self) -> bool {
+        self.value().is_pointer()
+    }
+
+    /// Returns a tag of a cons.
+    pub fn tag(&self) -> Result<Tag, Error> {
+        Ok(self.cons_index()?.1)
+    }
+
+    /// Returns a car of a cons.
+    pub fn car(&self) -> Result<Self, Error> {
+        self.field(0)
+    }
+
+    /// Returns a cdr of a cons.
+    pub fn cdr(&self) -> Result<Self, Error> {
+        self.field(1)
+    }
+
+    /// Sets a car of a cons.
+    pub fn set_car(&self, value: &Self) -> Result<(), Error> {
+        self.set_field(0, value)
+    }
+
+    /// Sets a cdr of a cons.
+    pub fn set_cdr(&self, value: &Self) -> Result<(), Error> {
+        self.set_field(1, value)
+    }
+
+    fn value(&self) -> V {
+        self.scope.slots.borrow().get(self.slot)
+    }
+
+    fn cons_index(&self) -> Result<(usize, Tag), Error> {
+        let cons = self.value().to_cons().map_err(|_| Error::ConsExpected)?;
+
+        Ok((cons.index(), cons.tag()))
+    }
+
+    fn field(&self, offset: usize) -> Result<Self, Error> {
+        let (index, _) = self.cons_index()?;
+        let value = self.scope.memory.borrow().get(index + offset)?;
+
+        self.scope.handle(value)
+    }
+
+    fn set_field(&self, offset: usize, value: &Self) -> Result<(), Error> {
+        let (index, _) = self.cons_index()?;
+
+        self.scope
+            .memory
+            .borrow_mut()
+            .set(index + offset, value.value())
+    }
+}
+
+impl<V: Value, H: Heap<V>> Debug for Handle<'_, V, H> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Handle")
+            .field("value", &self.value())
+            .finish()
+    }
+}
+
+impl<V: Value, H: Heap<V>> Drop for Handle<'_, V, H> {
+    // Slots are borrowed only inside methods of a scope, which never drop
+    // handles while borrowing them.
+    fn drop(&mut self) {
+        self.scope.slots.borrow_mut().remove(self.slot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Semispace, Value64};
+    use pretty_assertions::assert_eq;
+
+    const HEAP_SIZE: usize = 1 << 8;
+
+    type TestMemory = Memory<Value64, [Value64; HEAP_SIZE]>;
+
+    fn create_memory() -> TestMemory {
+        Memory::new([Default::default(); _]).unwrap()
+    }
+
+    fn collect_garbages<H: Heap<Value64>>(scope: &Scope<Value64, H>) {
+        let mut slots = scope.slots.borrow_mut();
+
+        scope
+            .memory
+            .borrow_mut()
+            .collect_garbages_with(&mut [], slots.values_mut())
+            .unwrap();
+    }
+
+    #[test]
+    fn keep_values_alive() {
+        Scope::enter(&mut create_memory(), |scope| {
+            let list = {
+                let one = scope.number(1).unwrap();
+                let two = scope.number(2).unwrap();
+                let null = scope.number(0).unwrap();
+                let cdr = scope.cons(&two, &null).unwrap();
+
+                scope.cons(&one, &cdr).unwrap()
+            };
+
+            for _ in 0..HEAP_SIZE {
+                let null = scope.number(0).unwrap();
+                scope.cons(&null, &null).unwrap();
+            }
+
+            collect_garbages(scope);
+
+            assert_eq!(list.car().unwrap().to_number(), Ok(1));
+            assert_eq!(list.cdr().unwrap().car().unwrap().to_number(), Ok(2));
+            assert_eq!(list.cdr().unwrap().cdr().unwrap().to_number(), Ok(0));
+        });
+    }
+
+    #[test]
+    fn keep_values_alive_while_copying() {
+        let mut memory =
+            Memory::<Value64, _>::new(Semispace::new([Default::default(); HEAP_SIZE])).unwrap();
+
+        Scope::enter(&mut memory, |scope| {
+            let null = scope.number(0).unwrap();
+            let mut list = scope.cons(&null, &null).unwrap();
+
+            for index in 1..4 {
+                let number = scope.number(index).unwrap();
+                list = scope.cons(&number, &list).unwrap();
+            }
+
+            for _ in 0..HEAP_SIZE {
+                scope.cons(&null, &null).unwrap();
+            }
+
+            collect_garbages(scope);
+
+            for index in (0..4).rev() {
+                assert_eq!(list.car().unwrap().to_number(), Ok(index));
+                list = list.cdr().unwrap();
+            }
+
+            assert_eq!(list.to_number(), Ok(0));
+        });
+    }
+
+    #[test]
+    fn reuse_slots() {
+        Scope::enter(&mut create_memory(), |scope| {
+            let x = scope.number(1).unwrap();
+            let y = scope.number(2).unwrap();
+            let z = scope.number(3).unwrap();
+
+            assert_eq!((x.slot, y.slot, z.slot), (0, 1, 2));
+
+            drop(x);
+            drop(y);
+
+            assert_eq!(scope.number(4).unwrap().slot, 1);
+            assert_eq!(scope.number(5).unwrap().slot, 1);
+            assert_eq!(z.to_number(), Ok(3));
+            assert_eq!(scope.slots.borrow().length, 3);
+        });
+    }
+
+    #[test]
+    fn release_slots_of_dropped_handles() {
+        Scope::enter(&mut create_memory(), |scope| {
+            for _ in 0..HEAP_SIZE {
+                let x = scope.number(1).unwrap();
+                let y = scope.number(2).unwrap();
+
+                drop(x);
+                drop(y);
+            }
+
+            assert_eq!(scope.slots.borrow().length, 2);
+        });
+    }
+
+    #[test]
+    fn fail_to_create_too_many_handles() {
+        Scope::enter(&mut create_memory(), |scope| {
+            let handles = (0..HANDLE_COUNT)
+                .map(|_| scope.number(0).unwrap())
+                .collect::<alloc::vec::Vec<_>>();
+
+            assert_eq!(scope.number(0).err(), Some(Error::HandleLimit));
+
+            drop(handles);
+
+            assert_eq!(scope.number(42).unwrap().to_number(), Ok(42));
+        });
+    }
+
+    #[test]
+    fn keep_outer_roots() {
+        let mut memory = create_memory();
+        memory.push_root(42.into()).unwrap();
+
+        Scope::enter(&mut memory, |scope| {
+            let null = scope.number(0).unwrap();
+            let cons = scope.cons(&null, &null).unwrap();
+
+            drop(null);
+            collect_garbages(scope);
+
+            assert_eq!(cons.car().unwrap().to_number(), Ok(0));
+        });
+
+        assert_eq!(memory.root_count(), 1);
+        assert_eq!(memory.pop_root(), Ok(42.into()));
+    }
+
+    #[test]
+    fn set_fields() {
+        Scope::enter(&mut create_memory(), |scope| {
+            let one = scope.number(1).unwrap();
+            let two = scope.number(2).unwrap();
+            let cons = scope.cons(&one, &one).unwrap();
+
+            cons.set_car(&two).unwrap();
+            cons.set_cdr(&cons).unwrap();
+
+            assert_eq!(cons.car().unwrap().to_number(), Ok(2));
+            assert_eq!(cons.cdr().unwrap().car().unwrap().to_number(), Ok(2));
+            assert_eq!(cons.tag(), Ok(0));
+            assert!(cons.is_cons());
+            assert!(!one.is_cons());
+        });
+    }
+
+    #[test]
+    fn fail_to_access_fields() {
+        Scope::enter(&mut create_memory(), |scope| {
+            let one = scope.number(1).unwrap();
+            let cons = scope.cons(&one, &one).unwrap();
+
+            assert_eq!(one.car().err(), Some(Error::ConsExpected));
+            assert_eq!(one.set_cdr(&one), Err(Error::ConsExpected));
+            assert_eq!(one.tag(), Err(Error::ConsExpected));
+            assert_eq!(cons.to_number(), Err(Error::NumberExpected));
+        });
+    }
+
+    #[test]
+    fn access_registers() {
+        let mut memory = create_memory();
+        let root = memory.allocate(1.into(), 2.into()).unwrap();
+        memory.set_root(root.into());
+
+        Scope::enter(&mut memory, |scope| {
+            let value = scope.register(0b11).unwrap();
+            scope.set_register(0b10, &value).unwrap();
+
+            assert_eq!(scope.register(0b10).unwrap().to_number(), Ok(2));
+            assert_eq!(scope.register(0b1).err(), Some(Error::InvalidRegister));
+        });
+    }
+}
@@ -1,5 +1,9 @@
 /// A heap.
-pub trait Heap<T>: AsRef<[T]> + AsMut<[T]> {}
+pub trait Heap<T>: AsRef<[T]> + AsMut<[T]> {
+    /// Whether garbage collection copies live conses between two semispaces
+    /// instead of marking and sweeping them in place.
+    const COPYING: bool = false;
+}
 
 impl<T> Heap<T> for &mut [T] {}
 
@@ -7,3 +11,36 @@ impl<T, const N: usize> Heap<T> for [T; N] {}
 
 #[cfg(feature = "alloc")]
 impl<T> Heap<T> for alloc::vec::Vec<T> {}
+
+/// A heap collected by a copying garbage collector.
+///
+/// It splits a heap into two semispaces and allocates conses from only one of
+/// them at a time. Garbage collection copies live conses into the other
+/// semispace compacting them, and conses are allocated in order by bumping a
+/// pointer afterwards. It can hold only a half of the conses of its inner
+/// heap.
+#[derive(Clone, Debug, Default)]
+pub struct Semispace<H>(H);
+
+impl<H> Semispace<H> {
+    /// Creates a heap.
+    pub const fn new(heap: H) -> Self {
+        Self(heap)
+    }
+}
+
+impl<T, H: AsRef<[T]>> AsRef<[T]> for Semispace<H> {
+    fn as_ref(&self) -> &[T] {
+        self.0.as_ref()
+    }
+}
+
+impl<T, H: AsMut<[T]>> AsMut<[T]> for Semispace<H> {
+    fn as_mut(&mut self) -> &mut [T] {
+        self.0.as_mut()
+    }
+}
+
+impl<T, H: Heap<T>> Heap<T> for Semispace<H> {
+    const COPYING: bool = true;
+}
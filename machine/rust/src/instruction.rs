@@ -1,15 +1,156 @@
 use crate::cons::Tag;
 
 /// An instruction.
+///
+/// An instruction is encoded into a tag of a cons in a code list. The low
+/// `BITS` bits of the tag are an opcode, which is an index of the instruction
+/// in `ALL`. The other high bits are a register address.
+///
+/// ```text
+/// +------------------------+--------+
+/// |        register        | opcode |
+/// +------------------------+--------+
+///   Tag::BITS - BITS bits    BITS bits
+/// ```
+///
+/// A new instruction is added at the end of `ALL` so that existing opcodes do
+/// not change. If the opcodes do not fit into `BITS` bits anymore, the bits
+/// are widened at the cost of register addresses.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Instruction {
     /// Creates a cons.
     Cons,
     /// Moves a value.
     Move,
+    /// Runs an operation in an operation set.
+    Operate,
+    /// Jumps to a code list if a value is zero.
+    If,
+    /// Calls a procedure.
+    Call,
+    /// Returns from a procedure.
+    Return,
 }
 
 impl Instruction {
-    pub(crate) const CONS: Tag = Self::Cons as _;
-    pub(crate) const MOVE: Tag = Self::Move as _;
+    /// A number of bits of an opcode.
+    pub(crate) const BITS: u32 = 3;
+    /// A number of register addresses.
+    pub(crate) const REGISTERS: usize = 1 << (Tag::BITS - Self::BITS);
+    /// Instructions in the order of their opcodes.
+    pub(crate) const ALL: &[Self] = &[
+        Self::Cons,
+        Self::Move,
+        Self::Operate,
+        Self::If,
+        Self::Call,
+        Self::Return,
+    ];
+
+    const MASK: Tag = (1 << Self::BITS) - 1;
+
+    /// Decodes an instruction from a tag.
+    pub(crate) const fn from_tag(tag: Tag) -> Option<Self> {
+        let opcode = Self::opcode(tag) as usize;
+
+        if opcode < Self::ALL.len() {
+            Some(Self::ALL[opcode])
+        } else {
+            None
+        }
+    }
+
+    /// Decodes an opcode from a tag.
+    pub(crate) const fn opcode(tag: Tag) -> Tag {
+        tag & Self::MASK
+    }
+
+    /// Decodes a register address from a tag.
+    pub(crate) const fn register(tag: Tag) -> usize {
+        (tag >> Self::BITS) as _
+    }
+
+    /// Encodes an instruction with a register address into a tag.
+    pub(crate) const fn tag(self, register: usize) -> Tag {
+        (register << Self::BITS) as Tag | self as Tag
+    }
+
+    /// Returns `true` if an instruction has a register.
+    pub(crate) const fn has_register(self) -> bool {
+        !matches!(self, Self::Operate)
+    }
+
+    /// Returns `true` if an instruction has an operand.
+    pub(crate) const fn has_operand(self) -> bool {
+        !matches!(self, Self::Return)
+    }
+
+    /// Returns `true` if an operand of an instruction is a branch.
+    pub(crate) const fn has_branch(self) -> bool {
+        matches!(self, Self::If | Self::Call)
+    }
+
+    /// Returns a name.
+    pub(crate) const fn name(self) -> &'static str {
+        match self {
+            Self::Cons => "cons",
+            Self::Move => "move",
+            Self::Operate => "operate",
+            Self::If => "if",
+            Self::Call => "call",
+            Self::Return => "return",
+        }
+    }
+
+    /// Parses a name into an instruction.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|instruction| instruction.name() == name)
+    }
+}
+
+const _: () = assert!(Instruction::ALL.len() <= 1 << Instruction::BITS);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn order_opcodes() {
+        for (opcode, instruction) in Instruction::ALL.iter().enumerate() {
+            assert_eq!(*instruction as usize, opcode);
+        }
+    }
+
+    #[test]
+    fn round_trip_tag() {
+        for &instruction in Instruction::ALL {
+            for register in 0..Instruction::REGISTERS {
+                let tag = instruction.tag(register);
+
+                assert_eq!(Instruction::from_tag(tag), Some(instruction));
+                assert_eq!(Instruction::register(tag), register);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_unknown_opcode() {
+        for opcode in Instruction::ALL.len()..1 << Instruction::BITS {
+            assert_eq!(Instruction::from_tag(opcode as _), None);
+        }
+    }
+
+    #[test]
+    fn round_trip_name() {
+        for &instruction in Instruction::ALL {
+            assert_eq!(
+                Instruction::from_name(instruction.name()),
+                Some(instruction)
+            );
+        }
+    }
 }
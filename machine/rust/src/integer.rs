@@ -42,6 +42,21 @@ pub trait Integer:
 
     /// Converts an integer to `usize`.
     fn to_usize(self) -> usize;
+
+    /// Adds integers with overflow checking.
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    /// Subtracts integers with overflow checking.
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /// Multiplies integers with overflow checking.
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    /// Divides integers with overflow and zero division checking.
+    fn checked_div(self, other: Self) -> Option<Self>;
+
+    /// Calculates a remainder with overflow and zero division checking.
+    fn checked_rem(self, other: Self) -> Option<Self>;
 }
 
 macro_rules! impl_integer {
@@ -62,6 +77,26 @@ macro_rules! impl_integer {
             fn to_usize(self) -> usize {
                 self as _
             }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                Self::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                Self::checked_sub(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                Self::checked_mul(self, other)
+            }
+
+            fn checked_div(self, other: Self) -> Option<Self> {
+                Self::checked_div(self, other)
+            }
+
+            fn checked_rem(self, other: Self) -> Option<Self> {
+                Self::checked_rem(self, other)
+            }
         }
     };
 }
@@ -2,27 +2,51 @@
 
 #![no_std]
 
-#[cfg(feature = "alloc")]
+#[cfg(any(feature = "alloc", test))]
 extern crate alloc;
-#[cfg(test)]
+#[cfg(any(feature = "std", test))]
 extern crate std;
 
+mod assembler;
 mod config;
 mod cons;
+mod disassembler;
 mod error;
+mod handle;
 mod heap;
 mod instruction;
 mod integer;
 mod machine;
 mod memory;
 mod operation_set;
+mod output;
+#[cfg(feature = "trace")]
+mod trace;
 mod value;
+mod verifier;
 
+pub use assembler::{AssemblyError, AssemblyErrorKind, assemble};
 pub use cons::Cons;
-pub use error::Error;
-pub use heap::Heap;
+pub use disassembler::{DisassemblyError, disassemble, disassemble_bytecode};
+pub use error::{Error, RunError};
+pub use handle::{Handle, Scope};
+pub use heap::{Heap, Semispace};
 pub use integer::Integer;
 pub use machine::Machine;
 pub use memory::Memory;
-pub use operation_set::{OperationSet, VoidOperationSet};
+pub use operation_set::{
+    ArithmeticError, ArithmeticOperation, ArithmeticOperationSet, CombinedError,
+    CombinedOperationSet, Host, HostError, HostOperation, HostOperationSet, ListError,
+    ListOperation, ListOperationSet, OperationSet, SeededHost, VoidError, VoidOperationSet,
+};
+#[cfg(all(feature = "std", unix))]
+pub use operation_set::{FileMode, FileSystemError, FileSystemOperation, FileSystemOperationSet};
+#[cfg(feature = "std")]
+pub use operation_set::{IoError, IoOperation, IoOperationSet, SystemHost};
+#[cfg(feature = "alloc")]
+pub use operation_set::{RegistryError, RegistryOperationSet};
+pub use output::Output;
+#[cfg(feature = "trace")]
+pub use trace::{Location, Trace, TracedError};
 pub use value::{Value, Value16, Value32, Value64, Value128, ValueSize};
+pub use verifier::{Verifier, Violation, ViolationKind};
@@ -1,14 +1,42 @@
 use crate::{
-    Cons, Error, Heap, Integer, Memory, OperationSet, Value, config::INTEGER_BASE,
+    Cons, Error, Heap, Integer, Memory, OperationSet, Output, RunError, Scope, Value, Verifier,
+    config::{BYTECODE_MAGIC, BYTECODE_VERSION, INTEGER_BASE},
     instruction::Instruction,
+    value::number_from_i64,
 };
+#[cfg(feature = "trace")]
+use crate::{Location, Trace, TracedError};
+use core::{
+    future::poll_fn,
+    task::{Context, Poll, ready},
+};
+
+// An error of execution carrying a trace of executed instructions if the
+// `trace` feature is enabled.
+#[cfg(feature = "trace")]
+macro_rules! run_error {
+    ($error:ty, $value:ty) => {
+        TracedError<$error, $value>
+    };
+}
+
+#[cfg(not(feature = "trace"))]
+macro_rules! run_error {
+    ($error:ty, $value:ty) => {
+        RunError<$error>
+    };
+}
 
 /// A machine.
 #[derive(Debug)]
 pub struct Machine<V, H, const C: usize, O: OperationSet<V, H>> {
     memory: Memory<V, H>,
-    #[expect(dead_code)]
     operation_set: O,
+    pending: Option<usize>,
+    #[cfg(feature = "trace")]
+    trace: Trace<V>,
+    #[cfg(feature = "trace")]
+    failed: bool,
 }
 
 impl<V: Value, H: Heap<V>, const C: usize, O: OperationSet<V, H>> Machine<V, H, C, O> {
@@ -17,69 +45,422 @@ impl<V: Value, H: Heap<V>, const C: usize, O: OperationSet<V, H>> Machine<V, H,
         Ok(Self {
             memory: Memory::new(heap)?,
             operation_set,
+            pending: None,
+            #[cfg(feature = "trace")]
+            trace: Trace::new(),
+            #[cfg(feature = "trace")]
+            failed: false,
         })
     }
 
     /// Runs a program.
-    pub fn run(&mut self, program: impl IntoIterator<Item = u8>) -> Result<(), Error> {
+    ///
+    /// A program starts with a header of a magic number, a format version, a
+    /// value width in bits, the code register `C`, and a code section length
+    /// in bytes. The header is followed by a code section of instructions in
+    /// reverse order. Each instruction is a tag byte followed by its operand
+    /// encoded as an integer. A tag byte has an opcode in its low
+    /// `Instruction::BITS` bits and a register address in the other high bits.
+    ///
+    /// The operand of a `move` instruction is a register address of a value to
+    /// move. The operand of an `if` instruction is a number of instructions to
+    /// skip when its register value is zero. The operand of a `call`
+    /// instruction is a number of instructions to skip to reach a procedure.
+    /// Negative numbers skip backward. They are decoded into code lists
+    /// starting from the instructions they jump to.
+    ///
+    /// A `call` instruction pushes a continuation of a code list after it onto
+    /// a continuation chain in its register. A `return` instruction pops the
+    /// continuation and resumes it, or halts the machine if the chain is
+    /// empty. A `call` instruction followed by a `return` instruction of the
+    /// same register is a tail call and does not push any continuation.
+    ///
+    /// A program is verified before its first instruction runs. So every
+    /// register path in it needs to resolve in the root structure at that
+    /// point. Programs addressing registers built at runtime can be run by
+    /// [`initialize`](Self::initialize) and [`execute`](Self::execute)
+    /// instead. The machine halts when a code list in the code register `C`
+    /// is exhausted.
+    ///
+    /// With the `trace` feature, an error carries a trace of instructions
+    /// executed until it happens.
+    pub fn run(
+        &mut self,
+        program: impl IntoIterator<Item = u8>,
+    ) -> Result<(), run_error!(O::Error, V)> {
+        self.initialize(program)?;
+
+        if let Some(violation) = self.verify()?.next() {
+            return Err(Error::InvalidProgram(violation).into());
+        }
+
+        self.execute()
+    }
+
+    /// Runs a program and returns a value of a result register.
+    pub fn evaluate(
+        &mut self,
+        program: impl IntoIterator<Item = u8>,
+        register: usize,
+    ) -> Result<Output<'_, V, H>, run_error!(O::Error, V)> {
+        self.run(program)?;
+
+        Ok(self.output(register)?)
+    }
+
+    /// Returns a value of a register.
+    pub fn output(&self, register: usize) -> Result<Output<'_, V, H>, Error> {
+        Ok(Output::new(
+            &self.memory,
+            self.memory.get(self.index(register)?)?,
+        ))
+    }
+
+    /// Verifies a program in the code register `C`.
+    pub fn verify(&self) -> Result<Verifier<'_, V, H>, Error> {
+        Ok(Verifier::new(&self.memory, self.code()?))
+    }
+
+    /// Executes a program in the code register `C` without verification.
+    pub fn execute(&mut self) -> Result<(), run_error!(O::Error, V)> {
+        match self.execute_with(|operation_set, memory, code| {
+            Poll::Ready(operation_set.operate(memory, code))
+        }) {
+            Poll::Ready(result) => result,
+            Poll::Pending => unreachable!(),
+        }
+    }
+
+    /// Runs a program asynchronously.
+    ///
+    /// It is the same as [`run`](Self::run) except that operations are polled
+    /// by [`OperationSet::poll_operate`].
+    pub async fn run_async(
+        &mut self,
+        program: impl IntoIterator<Item = u8>,
+    ) -> Result<(), run_error!(O::Error, V)> {
         self.initialize(program)?;
 
-        while let Ok(mut cons) = self.memory.get(self.index(C)?)?.to_cons() {
-            while let Ok(instruction) = self.memory.get(cons.index() + 1)?.to_cons() {
-                let operand = self.memory.get(cons.index())?;
-                let tag = instruction.tag();
-                let index = self.index((tag >> 1) as _)?;
+        if let Some(violation) = self.verify()?.next() {
+            return Err(Error::InvalidProgram(violation).into());
+        }
+
+        self.execute_async().await
+    }
+
+    /// Executes a program in the code register `C` asynchronously without
+    /// verification.
+    pub async fn execute_async(&mut self) -> Result<(), run_error!(O::Error, V)> {
+        poll_fn(|context| self.poll_execute(context)).await
+    }
+
+    /// Polls execution of a program in the code register `C` without
+    /// verification.
+    ///
+    /// When an operation is pending, the machine suspends at the `operate`
+    /// instruction and returns `Poll::Pending`. The machine keeps the code of
+    /// the operation so that polling it again polls the operation first and
+    /// then resumes the execution after the instruction.
+    pub fn poll_execute(
+        &mut self,
+        context: &mut Context,
+    ) -> Poll<Result<(), run_error!(O::Error, V)>> {
+        self.execute_with(|operation_set, memory, code| {
+            operation_set.poll_operate(memory, code, context)
+        })
+    }
+
+    fn execute_with(
+        &mut self,
+        operate: impl FnMut(&mut O, &mut Memory<V, H>, usize) -> Poll<Result<(), O::Error>>,
+    ) -> Poll<Result<(), run_error!(O::Error, V)>> {
+        let poll = self.execute_instructions(operate);
+
+        #[cfg(feature = "trace")]
+        let poll = {
+            self.failed = matches!(poll, Poll::Ready(Err(_)));
+            poll.map_err(|error| TracedError::new(error, self.trace.clone()))
+        };
+
+        poll
+    }
+
+    fn execute_instructions(
+        &mut self,
+        mut operate: impl FnMut(&mut O, &mut Memory<V, H>, usize) -> Poll<Result<(), O::Error>>,
+    ) -> Poll<Result<(), RunError<O::Error>>> {
+        if let Some(code) = self.pending.take() {
+            ready!(self.operate(&mut operate, code))?;
+        }
+
+        while let Ok(cons) = self.code()?.to_cons()
+            && let Ok(instruction) = self.memory.get(cons.index() + 1)?.to_cons()
+        {
+            self.memory.set(self.index(C)?, instruction.into())?;
+
+            let operand = self.memory.get(cons.index())?;
+            let tag = instruction.tag();
+
+            #[cfg(feature = "trace")]
+            self.trace.push(Location::new(cons.index(), tag, operand));
+
+            match Instruction::from_tag(tag).ok_or(Error::UnknownInstruction)? {
+                Instruction::Cons => {
+                    let register = Instruction::register(tag);
+                    let cons = self
+                        .memory
+                        .allocate(operand, self.memory.get(self.index(register)?)?)?;
+                    self.memory.set(self.index(register)?, cons.into())?;
+                }
+                Instruction::Move => {
+                    let index = self.index(Instruction::register(tag))?;
 
-                match tag & 1 {
-                    Instruction::CONS => {
-                        let cons = self.memory.allocate(operand, self.memory.get(index)?)?;
-                        self.memory.set(index, cons.into())?;
+                    self.memory.set(
+                        index,
+                        self.memory
+                            .get(self.index(Self::number(operand)?.to_usize())?)?,
+                    )?;
+                }
+                Instruction::Operate => {
+                    ready!(self.operate(&mut operate, Self::number(operand)?.to_usize()))?;
+                }
+                Instruction::If => {
+                    if self.memory.get(self.index(Instruction::register(tag))?)?
+                        == Default::default()
+                    {
+                        self.memory.set(self.index(C)?, operand)?;
                     }
-                    instruction => {
-                        debug_assert_eq!(instruction, Instruction::MOVE);
-
-                        self.memory.set(
-                            index,
-                            self.memory.get(
-                                operand
-                                    .to_number()
-                                    .map_err(|_| Error::NumberExpected)?
-                                    .to_usize(),
-                            )?,
+                }
+                Instruction::Call => {
+                    let register = Instruction::register(tag);
+                    let tail = self.is_return(instruction, register)?;
+
+                    // Jump before allocation so that a procedure is kept alive
+                    // in the code register.
+                    self.memory.set(self.index(C)?, operand)?;
+
+                    if !tail {
+                        let continuation = self.memory.allocate(
+                            instruction.into(),
+                            self.memory.get(self.index(register)?)?,
                         )?;
+                        self.memory
+                            .set(self.index(register)?, continuation.into())?;
                     }
                 }
+                Instruction::Return => {
+                    let index = self.index(Instruction::register(tag))?;
+                    let Ok(continuation) = self.memory.get(index)?.to_cons() else {
+                        break;
+                    };
 
-                cons = instruction;
+                    self.memory
+                        .set(self.index(C)?, self.memory.get(continuation.index())?)?;
+                    self.memory
+                        .set(index, self.memory.get(continuation.index() + 1)?)?;
+                }
             }
         }
 
-        Ok(())
+        Poll::Ready(Ok(()))
+    }
+
+    // Runs an operation keeping its code while it is pending.
+    fn operate(
+        &mut self,
+        operate: &mut impl FnMut(&mut O, &mut Memory<V, H>, usize) -> Poll<Result<(), O::Error>>,
+        code: usize,
+    ) -> Poll<Result<(), RunError<O::Error>>> {
+        let poll = operate(&mut self.operation_set, &mut self.memory, code);
+
+        if poll.is_pending() {
+            self.pending = Some(code);
+        }
+
+        poll.map_err(RunError::Operation)
     }
 
-    fn index(&self, mut address: usize) -> Result<usize, Error> {
-        let mut index = Cons::from(self.memory.root()).index();
+    // Checks if a code list starts with a return through a continuation register
+    // so that a call before it becomes a tail call.
+    fn is_return(&self, code: Cons<V>, register: usize) -> Result<bool, Error> {
+        Ok(self
+            .memory
+            .get(code.index() + 1)?
+            .to_cons()
+            .is_ok_and(|cons| cons.tag() == Instruction::Return.tag(register)))
+    }
+
+    fn number(value: V) -> Result<V::Number, Error> {
+        value.to_number().map_err(|_| Error::NumberExpected)
+    }
+
+    /// Runs a closure with a scope of handles to values in a memory.
+    pub fn scope<T>(&mut self, run: impl for<'a> FnOnce(&'a Scope<'a, V, H>) -> T) -> T {
+        Scope::enter(&mut self.memory, run)
+    }
+
+    /// Sets a number of conses in a reserve of a memory.
+    ///
+    /// See [`Memory::set_reserve`] for details.
+    pub const fn set_reserve(&mut self, count: usize) {
+        self.memory.set_reserve(count);
+    }
+
+    /// Returns an operation set.
+    pub const fn operation_set(&self) -> &O {
+        &self.operation_set
+    }
+
+    /// Returns a trace of instructions executed until the last execution
+    /// error.
+    ///
+    /// The latest location in the trace is the last instruction that started
+    /// executing. It is the instruction that failed unless the execution
+    /// failed on fetching a next instruction after it. It returns `None` if
+    /// the last execution did not fail.
+    #[cfg(feature = "trace")]
+    pub const fn error_trace(&self) -> Option<&Trace<V>> {
+        if self.failed { Some(&self.trace) } else { None }
+    }
+
+    pub(crate) const fn memory(&self) -> &Memory<V, H> {
+        &self.memory
+    }
 
-        while {
-            index += address & 1;
-            address >>= 1;
-            address > 1
-        } {}
+    pub(crate) fn code(&self) -> Result<V, Error> {
+        self.memory.get(self.index(C)?)
+    }
 
-        Ok(index)
+    fn index(&self, address: usize) -> Result<usize, Error> {
+        self.memory.resolve(address)
     }
 
-    #[expect(clippy::unused_self)]
-    fn initialize(&mut self, bytecode: impl IntoIterator<Item = u8>) -> Result<(), super::Error> {
+    /// Initializes a machine with a program.
+    ///
+    /// It decodes a program into the code register `C` without running it.
+    pub fn initialize(&mut self, bytecode: impl IntoIterator<Item = u8>) -> Result<(), Error> {
         let mut bytecode = bytecode.into_iter();
 
-        #[expect(clippy::while_let_on_iterator)]
-        while let Some(_byte) = bytecode.next() {}
+        self.pending = None;
+
+        #[cfg(feature = "trace")]
+        {
+            self.trace.clear();
+            self.failed = false;
+        }
+
+        if !self.memory.root().is_pointer() {
+            let root = self
+                .memory
+                .allocate(Default::default(), Default::default())?;
+            self.memory.set_root(root.into());
+        }
+
+        let length = Self::decode_header(&mut bytecode)?;
+
+        let cons = self
+            .memory
+            .allocate(Default::default(), Default::default())?;
+        self.memory.set(self.index(C)?, cons.into())?;
+
+        let mut count = 0;
+        let mut code = bytecode.by_ref().take(length).inspect(|_| count += 1);
+
+        while let Some(tag) = code.next() {
+            let operand = Self::decode_operand(&mut code)?;
+            let cons = self
+                .memory
+                .allocate(operand, Cons::from(self.code()?).set_tag(tag).into())?;
+            self.memory.set(self.index(C)?, cons.into())?;
+        }
+
+        if count < length {
+            return Err(Error::BytecodeEnd);
+        } else if bytecode.next().is_some() {
+            return Err(Error::TrailingBytecode);
+        }
+
+        self.decode_branches()
+    }
+
+    // Replaces relative offsets of branch instructions with code lists.
+    fn decode_branches(&mut self) -> Result<(), Error> {
+        let code = Cons::from(self.code()?);
+        let mut cons = code;
+        let mut position = 0usize;
+
+        while let Ok(next) = self.memory.get(cons.index() + 1)?.to_cons() {
+            position += 1;
+
+            if Instruction::from_tag(next.tag()).is_some_and(Instruction::has_branch) {
+                let offset = Self::number(self.memory.get(cons.index())?)?;
+                let target = if offset < Default::default() {
+                    let target = position
+                        .checked_sub((!offset).to_usize() + 1)
+                        .ok_or(Error::InvalidBranch)?;
+
+                    self.skip(code, target)?
+                } else {
+                    self.skip(next, offset.to_usize())?
+                };
+
+                self.memory.set(cons.index(), target.into())?;
+            }
+
+            cons = next;
+        }
 
         Ok(())
     }
 
-    #[expect(dead_code)]
+    fn skip(&self, mut cons: Cons<V>, count: usize) -> Result<Cons<V>, Error> {
+        for _ in 0..count {
+            cons = self
+                .memory
+                .get(cons.index() + 1)?
+                .to_cons()
+                .map_err(|_| Error::InvalidBranch)?;
+        }
+
+        Ok(cons.set_tag(0))
+    }
+
+    fn decode_header(bytecode: &mut impl Iterator<Item = u8>) -> Result<usize, Error> {
+        for byte in BYTECODE_MAGIC {
+            if bytecode.next().ok_or(Error::BytecodeEnd)? != byte {
+                return Err(Error::InvalidMagic);
+            }
+        }
+
+        if bytecode.next().ok_or(Error::BytecodeEnd)? != BYTECODE_VERSION {
+            return Err(Error::UnsupportedVersion);
+        } else if bytecode.next().ok_or(Error::BytecodeEnd)? as usize != V::Number::BITS {
+            return Err(Error::ValueWidthMismatch);
+        } else if Self::decode_integer(bytecode)? != C as u64 {
+            return Err(Error::CodeRegisterMismatch);
+        }
+
+        Ok(Self::decode_integer(bytecode)? as _)
+    }
+
+    fn decode_operand(bytecode: &mut impl Iterator<Item = u8>) -> Result<V, Error> {
+        let integer = Self::decode_integer(bytecode)?;
+
+        Ok(number_from_i64::<V>(if integer & 1 == 0 {
+            (integer >> 1) as _
+        } else {
+            !(integer >> 1) as _
+        })
+        .ok_or(Error::InvalidOperand)?
+        .into())
+    }
+
+    fn decode_integer(bytecode: &mut impl Iterator<Item = u8>) -> Result<u64, Error> {
+        let byte = bytecode.next().ok_or(Error::BytecodeEnd)?;
+
+        Self::decode_integer_tail(bytecode, byte, INTEGER_BASE)
+    }
+
     fn decode_integer_tail(
         bytecode: &mut impl Iterator<Item = u8>,
         mut x: u8,
@@ -89,8 +470,11 @@ impl<V: Value, H: Heap<V>, const C: usize, O: OperationSet<V, H>> Machine<V, H,
 
         while x & 1 != 0 {
             x = bytecode.next().ok_or(Error::BytecodeEnd)?;
-            y += (x as u64 >> 1) * base;
-            base *= INTEGER_BASE;
+            y = base
+                .checked_mul(x as u64 >> 1)
+                .and_then(|z| y.checked_add(z))
+                .ok_or(Error::InvalidOperand)?;
+            base = base.saturating_mul(INTEGER_BASE);
         }
 
         Ok(y)
@@ -100,9 +484,59 @@ impl<V: Value, H: Heap<V>, const C: usize, O: OperationSet<V, H>> Machine<V, H,
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Value64, operation_set::VoidOperationSet};
+    use crate::{Value64, VoidError, assemble, operation_set::VoidOperationSet};
+    use alloc::vec::Vec;
 
     const HEAP_SIZE: usize = 1 << 8;
+    const REGISTER: usize = 0b11;
+
+    type TestMachine = Machine<Value64, [Value64; HEAP_SIZE], 0b10, VoidOperationSet>;
+
+    fn create_machine() -> TestMachine {
+        Machine::new([Default::default(); _], VoidOperationSet::new()).unwrap()
+    }
+
+    // Drops a trace from an error if any.
+    fn untrace<T, E>(result: Result<T, run_error!(E, Value64)>) -> Result<T, RunError<E>> {
+        #[cfg(feature = "trace")]
+        let result = result.map_err(RunError::from);
+
+        result
+    }
+
+    fn encode(code: &[u8]) -> Vec<u8> {
+        [
+            &BYTECODE_MAGIC[..],
+            &[BYTECODE_VERSION, 64, 0b10 << 1, (code.len() << 1) as _],
+            code,
+        ]
+        .concat()
+    }
+
+    fn get_register<H: Heap<Value64>, O: OperationSet<Value64, H>>(
+        machine: &Machine<Value64, H, 0b10, O>,
+    ) -> Value64 {
+        machine
+            .memory
+            .get(machine.index(REGISTER).unwrap())
+            .unwrap()
+    }
+
+    fn assert_list<H: Heap<Value64>, O: OperationSet<Value64, H>>(
+        machine: &Machine<Value64, H, 0b10, O>,
+        mut value: Value64,
+        values: &[i64],
+    ) {
+        for &expected in values {
+            let cons = value.to_cons().unwrap();
+
+            assert_eq!(machine.memory.get(cons.index()).unwrap(), expected.into());
+
+            value = machine.memory.get(cons.index() + 1).unwrap();
+        }
+
+        assert!(!value.is_pointer());
+    }
 
     #[test]
     fn index() {
@@ -124,4 +558,958 @@ mod tests {
             2i64.into()
         );
     }
+
+    #[test]
+    fn run_empty_program() {
+        let mut machine = create_machine();
+
+        machine.run(encode(&[])).unwrap();
+
+        assert_eq!(get_register(&machine), Default::default());
+    }
+
+    #[test]
+    fn run_assembled_program() {
+        let mut machine = create_machine();
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>("cons 0b11 1\ncons 0b11 2", &mut bytecode).unwrap();
+        machine.run(bytecode).unwrap();
+
+        assert_list(&machine, get_register(&machine), &[2, 1]);
+    }
+
+    fn create_registers<H: Heap<Value64>, O: OperationSet<Value64, H>>(
+        machine: &mut Machine<Value64, H, 0b10, O>,
+        count: usize,
+    ) {
+        let mut registers = Value64::default();
+
+        for _ in 0..count {
+            registers = machine
+                .memory
+                .allocate(Default::default(), registers)
+                .unwrap()
+                .into();
+        }
+
+        let root = machine
+            .memory
+            .allocate(Default::default(), registers)
+            .unwrap();
+        machine.memory.set_root(root.into());
+    }
+
+    fn get<H: Heap<Value64>, O: OperationSet<Value64, H>>(
+        machine: &Machine<Value64, H, 0b10, O>,
+        address: usize,
+    ) -> Value64 {
+        machine.memory.get(machine.index(address).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn move_value() {
+        let mut machine = create_machine();
+        create_registers(&mut machine, 1);
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(
+            "cons 0b101 1\ncons 0b101 2\nmove 0b111 0b101",
+            &mut bytecode,
+        )
+        .unwrap();
+        machine.run(bytecode).unwrap();
+
+        assert_list(&machine, get(&machine, 0b111), &[2, 1]);
+    }
+
+    #[test]
+    fn evaluate_program() {
+        let mut machine = create_machine();
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>("cons 0b11 1\ncons 0b11 2", &mut bytecode).unwrap();
+        let output = machine.evaluate(bytecode, REGISTER).unwrap();
+
+        assert_eq!(output.car().unwrap().to_number(), Ok(2));
+        assert_eq!(output.cdr().unwrap().car().unwrap().to_number(), Ok(1));
+        assert_eq!(output.cdr().unwrap().cdr().unwrap().to_number(), Ok(0));
+    }
+
+    #[test]
+    fn handle_output_in_scope() {
+        let mut machine = create_machine();
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>("cons 0b11 1", &mut bytecode).unwrap();
+        machine.run(bytecode).unwrap();
+
+        machine.scope(|scope| {
+            let list = scope.register(REGISTER).unwrap();
+            let two = scope.number(2).unwrap();
+
+            scope
+                .set_register(REGISTER, &scope.cons(&two, &list).unwrap())
+                .unwrap();
+        });
+
+        assert_list(&machine, get_register(&machine), &[2, 1]);
+    }
+
+    #[test]
+    fn evaluate_program_with_number_result() {
+        let mut machine = create_machine();
+
+        assert_eq!(
+            machine.evaluate(encode(&[]), REGISTER).unwrap().to_number(),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn fail_to_evaluate_with_invalid_register() {
+        let mut machine = create_machine();
+
+        assert_eq!(
+            untrace(machine.evaluate(encode(&[]), 0b1)).err(),
+            Some(RunError::Machine(Error::InvalidRegister))
+        );
+    }
+
+    #[test]
+    fn fail_with_out_of_memory() {
+        let mut machine = create_machine();
+        create_registers(&mut machine, 1);
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>("cons 0b101 1\nif 0b111 -2", &mut bytecode).unwrap();
+
+        assert_eq!(
+            untrace(machine.run(bytecode)),
+            Err(RunError::Machine(Error::OutOfMemory))
+        );
+    }
+
+    mod branch {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn run(source: &str) -> TestMachine {
+            let mut machine = create_machine();
+            create_registers(&mut machine, 1);
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>(source, &mut bytecode).unwrap();
+            machine.run(bytecode).unwrap();
+
+            machine
+        }
+
+        #[test]
+        fn branch_on_zero() {
+            let machine = run("if 0b111 1\ncons 0b101 1\ncons 0b101 2");
+
+            assert_list(&machine, get(&machine, 0b101), &[2]);
+        }
+
+        #[test]
+        fn fall_through_on_non_zero() {
+            let machine = run("cons 0b111 0\nif 0b111 1\ncons 0b101 1\ncons 0b101 2");
+
+            assert_list(&machine, get(&machine, 0b101), &[2, 1]);
+        }
+
+        #[test]
+        fn branch_to_end() {
+            let machine = run("if 0b111 2\ncons 0b101 1\ncons 0b101 2");
+
+            assert_eq!(get(&machine, 0b101), Default::default());
+        }
+
+        #[test]
+        fn branch_without_skip() {
+            let machine = run("if 0b111 0\ncons 0b101 1");
+
+            assert_list(&machine, get(&machine, 0b101), &[1]);
+        }
+
+        #[test]
+        fn loop_over_list() {
+            let mut machine = create_machine();
+            create_registers(&mut machine, 2);
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>(
+                "
+                cons 0b101 3
+                cons 0b101 2
+                cons 0b101 1
+                move 0b1011 0b10 ; save a loop label
+                if 0b101 3
+                cons 0b1111 0
+                move 0b101 0b1101 ; drop a list element
+                move 0b10 0b1011 ; jump to the loop label
+                ",
+                &mut bytecode,
+            )
+            .unwrap();
+            // Skip verification as registers are built at runtime.
+            machine.initialize(bytecode).unwrap();
+            machine.execute().unwrap();
+
+            assert_eq!(get(&machine, 0b101), Default::default());
+            assert_list(&machine, get(&machine, 0b1111), &[0, 0, 0]);
+        }
+
+        #[test]
+        fn fail_to_decode_invalid_call() {
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>("call 0b11 1", &mut bytecode).unwrap();
+
+            assert_eq!(
+                create_machine().initialize(bytecode),
+                Err(Error::InvalidBranch)
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_invalid_branch() {
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>("if 0b11 2\ncons 0b11 1", &mut bytecode).unwrap();
+
+            assert_eq!(
+                create_machine().initialize(bytecode),
+                Err(Error::InvalidBranch)
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_negative_branch() {
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>("cons 0b11 0\nif 0b11 -3", &mut bytecode).unwrap();
+
+            assert_eq!(
+                create_machine().initialize(bytecode),
+                Err(Error::InvalidBranch)
+            );
+        }
+    }
+
+    mod call {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        const CONTINUATION: usize = 0b1111;
+
+        fn run(source: &str) -> TestMachine {
+            let mut machine = create_machine();
+            create_registers(&mut machine, 2);
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>(source, &mut bytecode).unwrap();
+            machine.run(bytecode).unwrap();
+
+            machine
+        }
+
+        #[test]
+        fn call_procedure() {
+            let machine = run("
+                call 0b1111 2
+                cons 0b101 1
+                return 0b1111
+                cons 0b101 2
+                return 0b1111
+            ");
+
+            assert_list(&machine, get(&machine, 0b101), &[1, 2]);
+            assert_eq!(get(&machine, CONTINUATION), Default::default());
+        }
+
+        #[test]
+        fn call_nested_procedures() {
+            let machine = run("
+                call 0b1111 2
+                cons 0b101 1
+                return 0b1111
+                call 0b1111 2
+                cons 0b101 2
+                return 0b1111
+                cons 0b101 3
+                return 0b1111
+            ");
+
+            assert_list(&machine, get(&machine, 0b101), &[1, 2, 3]);
+            assert_eq!(get(&machine, CONTINUATION), Default::default());
+        }
+
+        #[test]
+        fn keep_continuation() {
+            let machine = run("call 0b1111 1\ncons 0b101 1\ncons 0b101 2");
+
+            assert_list(&machine, get(&machine, 0b101), &[2]);
+            assert!(get(&machine, CONTINUATION).is_pointer());
+        }
+
+        #[test]
+        fn call_in_tail_position() {
+            let machine = run("call 0b1111 1\nreturn 0b1111\ncons 0b101 1");
+
+            assert_list(&machine, get(&machine, 0b101), &[1]);
+            assert_eq!(get(&machine, CONTINUATION), Default::default());
+        }
+
+        #[test]
+        fn call_recursively_in_tail_position() {
+            const LENGTH: usize = 96;
+
+            let mut machine = create_machine();
+            create_registers(&mut machine, 2);
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>(
+                &[
+                    &"cons 0b101 0\n".repeat(LENGTH),
+                    "
+                    move 0b1011 0b101
+                    call 0b1111 1
+                    return 0b1111
+                    ; a procedure iterating over a list
+                    if 0b1011 2
+                    move 0b1011 0b11011 ; move to the next element
+                    call 0b1111 -3
+                    return 0b1111
+                    ",
+                ]
+                .concat(),
+                &mut bytecode,
+            )
+            .unwrap();
+            // Skip verification as registers are built at runtime.
+            machine.initialize(bytecode).unwrap();
+            machine.execute().unwrap();
+
+            assert_list(&machine, get(&machine, 0b101), &[0; LENGTH]);
+            assert_eq!(get(&machine, 0b1011), Default::default());
+            assert_eq!(get(&machine, CONTINUATION), Default::default());
+        }
+
+        #[test]
+        fn branch_backward() {
+            let machine = run("
+                cons 0b101 1
+                if 0b1011 1
+                return 0b1111
+                cons 0b1011 0
+                if 0b1111 -5
+            ");
+
+            assert_list(&machine, get(&machine, 0b101), &[1, 1]);
+        }
+
+        #[test]
+        fn keep_procedure_alive_while_calling() {
+            let mut machine = create_machine();
+            let mut bytecode = Vec::new();
+
+            // A procedure is reachable only from call instructions after it.
+            assemble::<Value64, 0b10>(
+                "
+                cons 0b11 0
+                if 0b111 2
+                cons 0b101 1
+                return 0b111
+                call 0b111 -3
+                call 0b111 -4
+                call 0b111 -5
+                cons 0b101 9
+                ",
+                &mut bytecode,
+            )
+            .unwrap();
+            // Skip verification as registers are built at runtime.
+            machine.initialize(bytecode).unwrap();
+            machine.execute().unwrap();
+
+            assert_list(&machine, get(&machine, 0b101), &[9, 1, 1, 1]);
+            assert_eq!(get(&machine, 0b111), Default::default());
+        }
+
+        #[test]
+        fn halt_on_return_without_continuation() {
+            let machine = run("return 0b1111\ncons 0b101 1");
+
+            assert_eq!(get(&machine, 0b101), Default::default());
+        }
+    }
+
+    mod copying {
+        use super::*;
+        use crate::Semispace;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn run_programs_while_copying() {
+            let mut machine = Machine::<Value64, _, 0b10, _>::new(
+                Semispace::new([Default::default(); HEAP_SIZE]),
+                VoidOperationSet::new(),
+            )
+            .unwrap();
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>(
+                "
+                call 0b1111 2
+                cons 0b101 1
+                return 0b1111
+                call 0b1111 2
+                cons 0b101 2
+                return 0b1111
+                cons 0b101 3
+                return 0b1111
+                ",
+                &mut bytecode,
+            )
+            .unwrap();
+
+            for _ in 0..HEAP_SIZE {
+                create_registers(&mut machine, 2);
+                machine.run(bytecode.iter().copied()).unwrap();
+
+                assert_list(&machine, get(&machine, 0b101), &[1, 2, 3]);
+                assert_eq!(get(&machine, 0b1111), Default::default());
+            }
+        }
+    }
+
+    mod operation {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[derive(Debug, Default)]
+        struct FakeOperationSet {
+            codes: Vec<usize>,
+        }
+
+        impl<V: Value, H: Heap<V>> OperationSet<V, H> for FakeOperationSet {
+            type Error = usize;
+
+            fn operate(&mut self, memory: &mut Memory<V, H>, code: usize) -> Result<(), usize> {
+                if code == 0 {
+                    return Err(42);
+                }
+
+                self.codes.push(code);
+
+                let index = memory.resolve(REGISTER).unwrap();
+                let cons = memory
+                    .allocate(
+                        V::Number::from_usize(code).into(),
+                        memory.get(index).unwrap(),
+                    )
+                    .unwrap();
+                memory.set(index, cons.into()).unwrap();
+
+                Ok(())
+            }
+        }
+
+        fn run(
+            source: &str,
+        ) -> Result<Machine<Value64, [Value64; HEAP_SIZE], 0b10, FakeOperationSet>, RunError<usize>>
+        {
+            let mut machine = Machine::new([Default::default(); _], FakeOperationSet::default())?;
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>(source, &mut bytecode).unwrap();
+            machine.run(bytecode)?;
+
+            Ok(machine)
+        }
+
+        #[test]
+        fn operate() {
+            let machine = run("operate 1\noperate 2").unwrap();
+
+            assert_eq!(machine.operation_set.codes, [1, 2]);
+        }
+
+        #[test]
+        fn operate_on_memory() {
+            let machine = run("cons 0b11 1\noperate 2").unwrap();
+
+            assert_list(&machine, get_register(&machine), &[2, 1]);
+        }
+
+        #[test]
+        fn fail_to_operate() {
+            assert_eq!(
+                run("operate 1\noperate 0\noperate 2").err(),
+                Some(RunError::Operation(42))
+            );
+        }
+
+        #[test]
+        fn fail_to_operate_with_void_operation_set() {
+            let mut machine = create_machine();
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>("operate 0", &mut bytecode).unwrap();
+
+            assert_eq!(
+                untrace(machine.run(bytecode)),
+                Err(RunError::Operation(VoidError::InvalidOperation))
+            );
+        }
+    }
+
+    mod suspension {
+        use super::*;
+        use crate::Semispace;
+        use core::{future::Future, pin::pin, task::Waker};
+        use pretty_assertions::assert_eq;
+
+        #[derive(Debug, Default)]
+        struct PendingOperationSet {
+            pending: usize,
+            polls: usize,
+            codes: Vec<usize>,
+        }
+
+        impl<V: Value, H: Heap<V>> OperationSet<V, H> for PendingOperationSet {
+            type Error = usize;
+
+            fn operate(&mut self, memory: &mut Memory<V, H>, code: usize) -> Result<(), usize> {
+                if code == 0 {
+                    return Err(42);
+                }
+
+                self.codes.push(code);
+                memory
+                    .push(REGISTER, V::Number::from_usize(code).into())
+                    .unwrap();
+
+                Ok(())
+            }
+
+            fn poll_operate(
+                &mut self,
+                memory: &mut Memory<V, H>,
+                code: usize,
+                context: &mut Context,
+            ) -> Poll<Result<(), usize>> {
+                self.polls += 1;
+
+                if self.pending > 0 {
+                    self.pending -= 1;
+                    // Move conses while an operation is pending.
+                    memory.collect_garbages().unwrap();
+                    context.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+
+                Poll::Ready(self.operate(memory, code))
+            }
+        }
+
+        type PendingMachine = Machine<Value64, [Value64; HEAP_SIZE], 0b10, PendingOperationSet>;
+
+        fn create_machine(source: &str, pending: usize) -> PendingMachine {
+            let mut machine = Machine::new(
+                [Default::default(); _],
+                PendingOperationSet {
+                    pending,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>(source, &mut bytecode).unwrap();
+            machine.initialize(bytecode).unwrap();
+
+            machine
+        }
+
+        fn block_on<F: Future>(future: F) -> F::Output {
+            let mut future = pin!(future);
+            let mut context = Context::from_waker(Waker::noop());
+
+            loop {
+                if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                    return output;
+                }
+            }
+        }
+
+        #[test]
+        fn suspend_and_resume() {
+            let mut machine = create_machine("cons 0b11 1\noperate 2\noperate 3", 2);
+            let mut context = Context::from_waker(Waker::noop());
+
+            for _ in 0..2 {
+                assert_eq!(
+                    machine.poll_execute(&mut context).map(untrace),
+                    Poll::Pending
+                );
+                assert_list(&machine, get_register(&machine), &[1]);
+            }
+
+            assert_eq!(
+                machine.poll_execute(&mut context).map(untrace),
+                Poll::Ready(Ok(()))
+            );
+            assert_eq!(machine.operation_set.codes, [2, 3]);
+            assert_eq!(machine.operation_set.polls, 4);
+            assert_list(&machine, get_register(&machine), &[3, 2, 1]);
+        }
+
+        #[test]
+        fn execute_synchronously() {
+            let mut machine = create_machine("operate 1\noperate 2", 2);
+
+            machine.execute().unwrap();
+
+            assert_eq!(machine.operation_set.codes, [1, 2]);
+            assert_eq!(machine.operation_set.polls, 0);
+        }
+
+        #[test]
+        fn run_asynchronously() {
+            let mut machine = Machine::<Value64, [Value64; HEAP_SIZE], 0b10, _>::new(
+                [Default::default(); _],
+                PendingOperationSet {
+                    pending: 3,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>("operate 1\noperate 2", &mut bytecode).unwrap();
+            block_on(machine.run_async(bytecode)).unwrap();
+
+            assert_eq!(machine.operation_set.codes, [1, 2]);
+            assert_list(&machine, get_register(&machine), &[2, 1]);
+        }
+
+        #[test]
+        fn resume_after_copying() {
+            let mut machine = Machine::<Value64, _, 0b10, _>::new(
+                Semispace::new([Default::default(); HEAP_SIZE]),
+                PendingOperationSet {
+                    pending: 2,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>("cons 0b11 1\noperate 2\noperate 3", &mut bytecode).unwrap();
+            block_on(machine.run_async(bytecode)).unwrap();
+
+            assert_eq!(machine.operation_set.codes, [2, 3]);
+            assert_list(&machine, get_register(&machine), &[3, 2, 1]);
+        }
+
+        #[test]
+        fn fail_to_operate_asynchronously() {
+            let mut machine = create_machine("operate 1\noperate 0\noperate 2", 1);
+
+            assert_eq!(
+                untrace(block_on(machine.execute_async())),
+                Err(RunError::Operation(42))
+            );
+            assert_eq!(machine.operation_set.codes, [1]);
+        }
+    }
+
+    #[cfg(feature = "trace")]
+    mod trace {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn initialize(source: &str) -> TestMachine {
+            let mut machine = create_machine();
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b10>(source, &mut bytecode).unwrap();
+            machine.initialize(bytecode).unwrap();
+
+            machine
+        }
+
+        #[test]
+        fn trace_failing_instruction() {
+            let mut machine = initialize("cons 0b11 1\ncons 0b11 2\nmove 0b11 0b111111");
+            let error = machine.execute().unwrap_err();
+
+            assert_eq!(error.error(), &RunError::Machine(Error::InvalidRegister));
+
+            let trace = error.trace();
+            let location = error.location().unwrap();
+
+            assert_eq!(
+                trace
+                    .iter()
+                    .map(|location| location.name())
+                    .collect::<Vec<_>>(),
+                [Some("cons"), Some("cons"), Some("move")]
+            );
+            assert_eq!(location.opcode(), Instruction::Move as _);
+            assert_eq!(location.register(), 0b11);
+            assert_eq!(location.operand(), 0b111111.into());
+            assert_eq!(
+                machine
+                    .memory
+                    .get(location.index() + 1)
+                    .unwrap()
+                    .to_cons()
+                    .unwrap()
+                    .tag(),
+                Instruction::Move.tag(0b11)
+            );
+        }
+
+        #[test]
+        fn trace_failing_operation() {
+            let mut machine = initialize("cons 0b11 1\noperate 3");
+            let error = machine.execute().unwrap_err();
+
+            assert_eq!(
+                error.error(),
+                &RunError::Operation(VoidError::InvalidOperation)
+            );
+            assert_eq!(error.location(), machine.error_trace().unwrap().last());
+
+            let location = error.location().copied().unwrap();
+
+            assert_eq!(location.name(), Some("operate"));
+            assert_eq!(location.operand(), 3.into());
+        }
+
+        #[test]
+        fn trace_nothing_before_execution() {
+            let mut machine = create_machine();
+            let error = machine.run([]).unwrap_err();
+
+            assert_eq!(error.error(), &RunError::Machine(Error::BytecodeEnd));
+            assert_eq!(error.location(), None);
+        }
+
+        #[test]
+        fn trace_nothing_on_success() {
+            let mut machine = initialize("cons 0b11 1");
+
+            machine.execute().unwrap();
+
+            assert!(machine.error_trace().is_none());
+        }
+
+        #[test]
+        fn clear_trace_on_initialization() {
+            let mut machine = initialize("operate 0");
+
+            assert!(machine.execute().is_err());
+            assert!(machine.error_trace().is_some());
+
+            let mut bytecode = Vec::new();
+            assemble::<Value64, 0b10>("cons 0b11 1", &mut bytecode).unwrap();
+            machine.initialize(bytecode).unwrap();
+
+            assert!(machine.error_trace().is_none());
+        }
+    }
+
+    mod decode {
+        use super::*;
+        use crate::Value16;
+        use pretty_assertions::assert_eq;
+
+        const CONS: u8 = Instruction::Cons.tag(REGISTER);
+
+        #[test]
+        fn decode_instruction() {
+            let mut machine = create_machine();
+
+            machine.run(encode(&[CONS, 168])).unwrap();
+
+            assert_list(&machine, get_register(&machine), &[42]);
+        }
+
+        #[test]
+        fn decode_instructions_in_reverse_order() {
+            let mut machine = create_machine();
+
+            machine.run(encode(&[CONS, 8, CONS, 4])).unwrap();
+
+            assert_list(&machine, get_register(&machine), &[2, 1]);
+        }
+
+        #[test]
+        fn decode_negative_operand() {
+            let mut machine = create_machine();
+
+            machine.run(encode(&[CONS, 2, CONS, 6])).unwrap();
+
+            assert_list(&machine, get_register(&machine), &[-1, -2]);
+        }
+
+        #[test]
+        fn decode_multi_byte_operand() {
+            let mut machine = create_machine();
+
+            machine.run(encode(&[CONS, 145, 2])).unwrap();
+
+            assert_list(&machine, get_register(&machine), &[100]);
+        }
+
+        #[test]
+        fn decode_minimum_operand() {
+            let mut machine = create_machine();
+
+            machine
+                .run(encode(&[CONS, 255, 255, 255, 255, 255, 255, 255, 255, 126]))
+                .unwrap();
+
+            assert_list(&machine, get_register(&machine), &[-(1 << 61)]);
+        }
+
+        #[test]
+        fn fail_to_decode_too_large_operand() {
+            assert_eq!(
+                create_machine().initialize(encode(&[CONS, 1, 1, 1, 1, 1, 1, 1, 1, 128])),
+                Err(Error::InvalidOperand)
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_overflowing_operand() {
+            assert_eq!(
+                create_machine().initialize(encode(&[
+                    CONS, 255, 255, 255, 255, 255, 255, 255, 255, 255, 255, 2
+                ])),
+                Err(Error::InvalidOperand)
+            );
+        }
+
+        #[test]
+        fn store_program_in_code_register() {
+            let mut machine = create_machine();
+
+            machine.initialize(encode(&[CONS, 8, CONS, 4])).unwrap();
+
+            let code = machine.memory.get(machine.index(0b10).unwrap()).unwrap();
+
+            assert_list(&machine, code, &[1, 2, 0]);
+
+            let cons = code.to_cons().unwrap();
+            assert_eq!(
+                machine
+                    .memory
+                    .get(cons.index() + 1)
+                    .unwrap()
+                    .to_cons()
+                    .unwrap()
+                    .tag(),
+                CONS
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_missing_operand() {
+            assert_eq!(
+                create_machine().initialize(encode(&[CONS])),
+                Err(Error::BytecodeEnd)
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_truncated_operand() {
+            assert_eq!(
+                create_machine().initialize(encode(&[CONS, 145])),
+                Err(Error::BytecodeEnd)
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_short_code_section() {
+            let mut bytecode = encode(&[CONS, 168]);
+            bytecode.pop();
+
+            assert_eq!(
+                create_machine().initialize(bytecode),
+                Err(Error::BytecodeEnd)
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_trailing_bytecode() {
+            let mut bytecode = encode(&[CONS, 168]);
+            bytecode.push(0);
+
+            assert_eq!(
+                create_machine().initialize(bytecode),
+                Err(Error::TrailingBytecode)
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_missing_header() {
+            assert_eq!(create_machine().initialize([]), Err(Error::BytecodeEnd));
+            assert_eq!(
+                create_machine().initialize(encode(&[])[..5].iter().copied()),
+                Err(Error::BytecodeEnd)
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_invalid_magic() {
+            let mut bytecode = encode(&[]);
+            bytecode[0] = b'x';
+
+            assert_eq!(
+                create_machine().initialize(bytecode),
+                Err(Error::InvalidMagic)
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_unsupported_version() {
+            let mut bytecode = encode(&[]);
+            bytecode[4] = BYTECODE_VERSION + 1;
+
+            assert_eq!(
+                create_machine().initialize(bytecode),
+                Err(Error::UnsupportedVersion)
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_value_width_mismatch() {
+            let mut bytecode = Vec::new();
+
+            assemble::<Value16, 0b10>("cons 0b11 42", &mut bytecode).unwrap();
+
+            assert_eq!(
+                create_machine().initialize(bytecode),
+                Err(Error::ValueWidthMismatch)
+            );
+        }
+
+        #[test]
+        fn fail_to_decode_code_register_mismatch() {
+            let mut bytecode = Vec::new();
+
+            assemble::<Value64, 0b11>("cons 0b11 42", &mut bytecode).unwrap();
+
+            assert_eq!(
+                create_machine().initialize(bytecode),
+                Err(Error::CodeRegisterMismatch)
+            );
+        }
+    }
 }
@@ -1,17 +1,95 @@
 //! The Moco command line tool.
 
 use core::error::Error;
-use moco_machine::{Machine, Value64, VoidOperationSet};
+#[cfg(feature = "std")]
+use moco_machine::IoOperationSet;
+#[cfg(not(feature = "std"))]
+use moco_machine::VoidOperationSet;
+use moco_machine::{Machine, Value64, assemble, disassemble_bytecode};
+#[cfg(feature = "std")]
+use std::io::{StdinLock, StdoutLock};
+use std::{
+    env, fs,
+    io::{Read, Write, stdin, stdout},
+};
 
 const HEAP_SIZE: usize = 1 << 16;
+const CODE_REGISTER: usize = 0b10;
+#[cfg(feature = "std")]
+const STACK_REGISTER: usize = 0b11;
+// Programs read standard input with I/O operations if the `std` feature is
+// enabled. So they are never read from standard input in that case.
+#[cfg(feature = "std")]
+const USAGE: &str = "usage: moco <asm|disasm|run <file>>";
+#[cfg(not(feature = "std"))]
+const USAGE: &str = "usage: moco <asm|disasm|run [<file>|-]>";
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let mut machine = Machine::<Value64, [Value64; HEAP_SIZE], 0b10, _>::new(
+    match env::args().nth(1).as_deref() {
+        Some("asm") => assemble_program(),
+        Some("disasm") => disassemble_program(),
+        Some("run") => run_program(env::args().nth(2).as_deref()),
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn assemble_program() -> Result<(), Box<dyn Error>> {
+    let mut source = String::new();
+    let mut bytecode = vec![];
+
+    stdin().read_to_string(&mut source)?;
+    assemble::<Value64, CODE_REGISTER>(&source, &mut bytecode)?;
+    stdout().write_all(&bytecode)?;
+
+    Ok(())
+}
+
+fn disassemble_program() -> Result<(), Box<dyn Error>> {
+    let mut bytecode = vec![];
+    let mut listing = String::new();
+
+    stdin().read_to_end(&mut bytecode)?;
+    disassemble_bytecode::<Value64, _, CODE_REGISTER>(
+        [Default::default(); HEAP_SIZE],
+        bytecode,
+        &mut listing,
+    )?;
+    stdout().write_all(listing.as_bytes())?;
+
+    Ok(())
+}
+
+// Runs a program in a file or standard input if no file or `-` is given and
+// standard input is not used by the program.
+fn run_program(path: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let bytecode = match path {
+        #[cfg(feature = "std")]
+        None | Some("-") => return Err(USAGE.into()),
+        #[cfg(not(feature = "std"))]
+        None | Some("-") => {
+            let mut bytecode = vec![];
+            stdin().read_to_end(&mut bytecode)?;
+            bytecode
+        }
+        Some(path) => fs::read(path)?,
+    };
+    let mut machine = Machine::<Value64, [Value64; HEAP_SIZE], CODE_REGISTER, _>::new(
         [Default::default(); HEAP_SIZE],
-        VoidOperationSet::new(),
+        operation_set(),
     )?;
 
-    machine.run([])?;
+    machine.run(bytecode)?;
 
     Ok(())
 }
+
+// Programs perform I/O only if the `std` feature is enabled.
+#[cfg(feature = "std")]
+fn operation_set() -> IoOperationSet<StdinLock<'static>, StdoutLock<'static>> {
+    IoOperationSet::new(stdin().lock(), stdout().lock(), STACK_REGISTER)
+}
+
+#[cfg(not(feature = "std"))]
+const fn operation_set() -> VoidOperationSet {
+    VoidOperationSet::new()
+}
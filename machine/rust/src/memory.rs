@@ -6,7 +6,14 @@ use crate::{Cons, error::Error, heap::Heap, value::Value};
 pub struct Memory<V, H> {
     heap: H,
     root: V,
+    roots: V,
+    root_count: usize,
+    globals: V,
+    space: usize,
     free: V,
+    free_count: usize,
+    reserve: usize,
+    reserved: bool,
 }
 
 impl<V: Value, H: Heap<V>> Memory<V, H> {
@@ -15,7 +22,14 @@ impl<V: Value, H: Heap<V>> Memory<V, H> {
         let mut this = Self {
             heap,
             root: Default::default(),
+            roots: Default::default(),
+            root_count: 0,
+            globals: Default::default(),
+            space: 0,
             free: Default::default(),
+            free_count: 0,
+            reserve: 0,
+            reserved: true,
         };
 
         this.collect_garbages()?;
@@ -23,6 +37,10 @@ impl<V: Value, H: Heap<V>> Memory<V, H> {
         Ok(this)
     }
 
+    pub(crate) fn size(&self) -> usize {
+        self.heap().len()
+    }
+
     fn heap(&self) -> &[V] {
         self.heap.as_ref()
     }
@@ -43,6 +61,172 @@ impl<V: Value, H: Heap<V>> Memory<V, H> {
         self.root = value;
     }
 
+    /// Pushes a value onto a root stack of a host.
+    ///
+    /// Values on the root stack are kept alive by garbage collection until
+    /// they are popped.
+    pub fn push_root(&mut self, value: V) -> Result<(), Error> {
+        self.roots = self.allocate(value, self.roots)?.into();
+        self.root_count += 1;
+
+        Ok(())
+    }
+
+    /// Pops a value from a root stack of a host.
+    pub fn pop_root(&mut self) -> Result<V, Error> {
+        let cons = self.roots.to_cons().map_err(|_| Error::ConsExpected)?;
+
+        self.roots = self.get(cons.index() + 1)?;
+        self.root_count -= 1;
+        self.get(cons.index())
+    }
+
+    /// Returns a number of values on a root stack of a host.
+    pub const fn root_count(&self) -> usize {
+        self.root_count
+    }
+
+    /// Returns a value in a global root slot.
+    ///
+    /// Slots not set yet have the default value.
+    pub fn global(&self, slot: usize) -> Result<V, Error> {
+        self.global_index(slot)?
+            .map_or(Ok(Default::default()), |index| self.get(index))
+    }
+
+    /// Sets a value in a global root slot.
+    ///
+    /// Values in global root slots are kept alive by garbage collection. Setting
+    /// a slot for the first time allocates memory.
+    pub fn set_global(&mut self, slot: usize, mut value: V) -> Result<(), Error> {
+        let length = self.length(self.globals)?;
+
+        if slot >= length {
+            self.push_root(value)?;
+
+            let result = (length..=slot).try_for_each(|_| {
+                self.globals = self.allocate(Default::default(), self.globals)?.into();
+                Ok(())
+            });
+
+            value = self.pop_root()?;
+            result?;
+        }
+
+        let index = self.global_index(slot)?.ok_or(Error::InvalidMemoryAccess)?;
+
+        self.set(index, value)
+    }
+
+    fn global_index(&self, slot: usize) -> Result<Option<usize>, Error> {
+        self.slot_index(self.globals, slot)
+    }
+
+    // Slots are numbered from the last element of a list so that new slots are
+    // prepended to the list.
+    fn slot_index(&self, list: V, slot: usize) -> Result<Option<usize>, Error> {
+        let length = self.length(list)?;
+        let Some(position) = slot
+            .checked_add(1)
+            .and_then(|count| length.checked_sub(count))
+        else {
+            return Ok(None);
+        };
+        let mut cons = Cons::from(list);
+
+        for _ in 0..position {
+            cons = Cons::from(self.get(cons.index() + 1)?);
+        }
+
+        Ok(Some(cons.index()))
+    }
+
+    fn length(&self, mut list: V) -> Result<usize, Error> {
+        let mut length = 0;
+
+        while let Ok(cons) = list.to_cons() {
+            length += 1;
+            list = self.get(cons.index() + 1)?;
+        }
+
+        Ok(length)
+    }
+
+    /// Returns a number of conses in a reserve.
+    #[inline]
+    pub const fn reserve(&self) -> usize {
+        self.reserve
+    }
+
+    /// Sets a number of conses in a reserve.
+    ///
+    /// Conses in a reserve are kept back from allocation until a memory runs
+    /// out. Then, the reserve is released so that a host can still allocate
+    /// some conses to handle the error. It is kept back again once garbage
+    /// collection frees more conses than the reserve.
+    #[inline]
+    pub const fn set_reserve(&mut self, count: usize) {
+        self.reserve = count;
+    }
+
+    /// Resolves a register address into a heap index.
+    ///
+    /// Bits of an address below its most significant bit select cars (`0`)
+    /// or cdrs (`1`) from the least significant bit starting at the root.
+    pub fn resolve(&self, address: usize) -> Result<usize, Error> {
+        if address < 2 {
+            return Err(Error::InvalidRegister);
+        }
+
+        let mut cons = self.root.to_cons().map_err(|_| Error::InvalidRegister)?;
+
+        for bit in 0..address.ilog2() - 1 {
+            cons = self
+                .get(cons.index() + (address >> bit & 1))?
+                .to_cons()
+                .map_err(|_| Error::InvalidRegister)?;
+        }
+
+        Ok(cons.index() + (address >> (address.ilog2() - 1) & 1))
+    }
+
+    /// Pushes a value onto a list in a register.
+    pub fn push(&mut self, address: usize, value: V) -> Result<(), Error> {
+        let cons = self.allocate(value, self.get(self.resolve(address)?)?)?;
+
+        self.set(self.resolve(address)?, cons.into())
+    }
+
+    /// Pops a value from a list in a register.
+    pub fn pop(&mut self, address: usize) -> Result<V, Error> {
+        let index = self.resolve(address)?;
+        let cons = self
+            .get(index)?
+            .to_cons()
+            .map_err(|_| Error::ConsExpected)?;
+
+        self.set(index, self.get(cons.index() + 1)?)?;
+        self.get(cons.index())
+    }
+
+    /// Returns a heap index of an element at a position from the top of a list
+    /// in a register without popping it.
+    pub fn peek(&self, address: usize, position: usize) -> Result<usize, Error> {
+        let mut cons = self
+            .get(self.resolve(address)?)?
+            .to_cons()
+            .map_err(|_| Error::ConsExpected)?;
+
+        for _ in 0..position {
+            cons = self
+                .get(cons.index() + 1)?
+                .to_cons()
+                .map_err(|_| Error::ConsExpected)?;
+        }
+
+        Ok(cons.index())
+    }
+
     /// Returns a value at an index.
     #[inline]
     pub fn get(&self, index: usize) -> Result<V, Error> {
@@ -64,10 +248,37 @@ impl<V: Value, H: Heap<V>> Memory<V, H> {
     }
 
     /// Allocates a cons.
+    ///
+    /// A car and a cdr are kept alive during allocation even if they are not
+    /// reachable from the root. It returns an out-of-memory error if no cons
+    /// is available out of a reserve even after garbage collection.
+    ///
+    /// If a heap is copying, garbage collection moves conses. Then, heap
+    /// indices obtained before allocation are stale and need to be resolved
+    /// again.
     #[inline]
     pub fn allocate(&mut self, car: V, cdr: V) -> Result<Cons<V>, Error> {
+        self.allocate_with(car, cdr, &mut [])
+    }
+
+    // Allocates a cons keeping extra roots alive. The roots are updated in place
+    // if conses are moved.
+    #[inline]
+    pub(crate) fn allocate_with(
+        &mut self,
+        mut car: V,
+        mut cdr: V,
+        roots: &mut [V],
+    ) -> Result<Cons<V>, Error> {
         if self.is_out_of_memory() || cfg!(feature = "gc_always") {
-            self.collect_garbages()?;
+            let mut values = [car, cdr];
+            self.collect_garbages_with(&mut values, roots)?;
+            [car, cdr] = values;
+
+            if self.is_out_of_memory() {
+                self.reserved = false;
+                return Err(Error::OutOfMemory);
+            }
         }
 
         self.allocate_unchecked(car, cdr)
@@ -75,9 +286,18 @@ impl<V: Value, H: Heap<V>> Memory<V, H> {
 
     #[inline]
     fn allocate_unchecked(&mut self, car: V, cdr: V) -> Result<Cons<V>, Error> {
-        let cons = Cons::from(self.free);
+        if self.free_count == 0 {
+            return Err(Error::OutOfMemory);
+        }
+
+        let cons = self.free.to_cons().map_err(|_| Error::OutOfMemory)?;
 
-        self.free = self.get(cons.index() + 1)?;
+        self.free = if H::COPYING {
+            Cons::new(cons.index() + 2).into()
+        } else {
+            self.get(cons.index() + 1)?
+        };
+        self.free_count -= 1;
         self.set(cons.index(), car)?;
         self.set(cons.index() + 1, cdr)?;
 
@@ -85,24 +305,49 @@ impl<V: Value, H: Heap<V>> Memory<V, H> {
     }
 
     #[inline]
-    fn is_out_of_memory(&self) -> bool {
-        !self.free.is_pointer()
+    const fn is_out_of_memory(&self) -> bool {
+        self.free_count <= if self.reserved { self.reserve } else { 0 }
     }
 
     pub(crate) fn collect_garbages(&mut self) -> Result<(), Error> {
-        self.mark()?;
-        self.sweep()?;
+        self.collect_garbages_with(&mut [], &mut [])
+    }
+
+    // Collects garbages keeping temporary values and extra roots alive as well
+    // as roots of a memory. They are updated in place if conses are moved.
+    pub(crate) fn collect_garbages_with(
+        &mut self,
+        temporaries: &mut [V],
+        roots: &mut [V],
+    ) -> Result<(), Error> {
+        if H::COPYING {
+            self.copy_all(temporaries, roots)?;
+        } else {
+            for value in [self.root, self.roots, self.globals] {
+                self.mark(value)?;
+            }
+
+            for &mut value in temporaries.iter_mut().chain(roots) {
+                self.mark(value)?;
+            }
+
+            self.sweep()?;
+        }
+
+        if self.free_count > self.reserve {
+            self.reserved = true;
+        }
 
         Ok(())
     }
 
-    fn mark(&mut self) -> Result<(), Error> {
-        if !self.root.is_pointer() {
+    fn mark(&mut self, value: V) -> Result<(), Error> {
+        if !value.is_pointer() {
             return Ok(());
         }
 
         let mut previous = V::default();
-        let mut current = self.root;
+        let mut current = value;
 
         loop {
             debug_assert!(current.is_pointer());
@@ -119,7 +364,7 @@ impl<V: Value, H: Heap<V>> Memory<V, H> {
                     self.set(cons.index(), value.mark(true))?;
                 }
             } else if cons.index().is_multiple_of(2) {
-                current = Cons::new(cons.index() + 1).into();
+                current = cons.set_index(cons.index() + 1).into();
             } else if !previous.is_pointer() {
                 break;
             } else {
@@ -141,6 +386,7 @@ impl<V: Value, H: Heap<V>> Memory<V, H> {
 
     fn sweep(&mut self) -> Result<(), Error> {
         self.free = Default::default();
+        self.free_count = 0;
 
         for index in (0..self.heap().len()).step_by(2) {
             let value = self.get(index)?;
@@ -153,6 +399,7 @@ impl<V: Value, H: Heap<V>> Memory<V, H> {
             } else {
                 self.set(index + 1, self.free)?;
                 self.free = Cons::new(index).into();
+                self.free_count += 1;
             }
         }
 
@@ -162,6 +409,65 @@ impl<V: Value, H: Heap<V>> Memory<V, H> {
 
         Ok(())
     }
+
+    // Copies live conses from a current semispace to the other one in the
+    // breadth-first order by Cheney's algorithm.
+    fn copy_all(&mut self, temporaries: &mut [V], roots: &mut [V]) -> Result<(), Error> {
+        let size = self.semispace_size();
+        let start = if self.space == 0 { size } else { 0 };
+        let mut end = start;
+
+        self.root = self.copy(self.root, &mut end)?;
+        self.roots = self.copy(self.roots, &mut end)?;
+        self.globals = self.copy(self.globals, &mut end)?;
+
+        for value in temporaries.iter_mut().chain(roots) {
+            *value = self.copy(*value, &mut end)?;
+        }
+
+        let mut index = start;
+
+        while index < end {
+            let value = self.copy(self.get(index)?, &mut end)?;
+            self.set(index, value)?;
+            index += 1;
+        }
+
+        self.space = start;
+        self.free = Cons::new(end).into();
+        self.free_count = (start + size - end) / 2;
+
+        Ok(())
+    }
+
+    // Copies a cons to the end of a new semispace unless it is copied already.
+    // A car of a copied cons is replaced with a marked forwarding pointer.
+    fn copy(&mut self, value: V, end: &mut usize) -> Result<V, Error> {
+        let Ok(cons) = value.to_cons() else {
+            return Ok(value);
+        };
+        let car = self.get(cons.index())?;
+
+        let index = if car.is_marked() {
+            Cons::from(car.mark(false)).index()
+        } else {
+            let index = *end;
+
+            self.set(index, car)?;
+            self.set(index + 1, self.get(cons.index() + 1)?)?;
+            self.set(cons.index(), V::from(Cons::new(index)).mark(true))?;
+            *end += 2;
+
+            index
+        };
+
+        Ok(cons.set_index(index).into())
+    }
+
+    // Returns a size of each semispace aligned to conses.
+    fn semispace_size(&self) -> usize {
+        self.heap().len() / 4 * 2
+    }
 }
 
 #[cfg(test)]
@@ -174,19 +480,26 @@ mod tests {
 
     const HEAP_SIZE: usize = 1 << 8;
 
-    fn assert_free_list<V: Value + Hash, const N: usize>(
-        memory: &Memory<V, [V; N]>,
-        allocations: usize,
-    ) {
-        let mut free = memory.free;
-        let mut length = 0;
+    fn assert_free_list<V: Value, H: Heap<V>>(memory: &Memory<V, H>, allocations: usize) {
+        if !H::COPYING {
+            let mut free = memory.free;
+            let mut length = 0;
 
-        while free.is_pointer() {
-            free = memory.get(Cons::from(free).index() + 1).unwrap();
-            length += 1;
+            while free.is_pointer() {
+                free = memory.get(Cons::from(free).index() + 1).unwrap();
+                length += 1;
+            }
+
+            assert_eq!(length, memory.free_count);
         }
 
-        assert_eq!(length, HEAP_SIZE / 2 - allocations);
+        let capacity = if H::COPYING {
+            memory.semispace_size()
+        } else {
+            memory.size()
+        } / 2;
+
+        assert_eq!(memory.free_count, capacity - allocations);
     }
 
     fn assert_equal_values<V: Value + Hash, const N: usize>(
@@ -235,8 +548,133 @@ mod tests {
         Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
     }
 
+    mod resolution {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn create_memory() -> Memory<Value64, [Value64; HEAP_SIZE]> {
+            let mut memory =
+                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
+
+            let root = memory
+                .allocate(Default::default(), Default::default())
+                .unwrap();
+            memory.set_root(root.into());
+            let car = memory.allocate(1.into(), 2.into()).unwrap();
+            memory.set(root.index(), car.into()).unwrap();
+            let cdr = memory.allocate(3.into(), 4.into()).unwrap();
+            memory.set(root.index() + 1, cdr.into()).unwrap();
+
+            memory
+        }
+
+        fn get(memory: &Memory<Value64, [Value64; HEAP_SIZE]>, address: usize) -> Value64 {
+            memory.get(memory.resolve(address).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn resolve_root_fields() {
+            let memory = create_memory();
+            let root = Cons::from(memory.root());
+
+            assert_eq!(memory.resolve(0b10), Ok(root.index()));
+            assert_eq!(memory.resolve(0b11), Ok(root.index() + 1));
+        }
+
+        #[test]
+        fn resolve_nested_fields() {
+            let memory = create_memory();
+
+            assert_eq!(get(&memory, 0b100), 1.into());
+            assert_eq!(get(&memory, 0b110), 2.into());
+            assert_eq!(get(&memory, 0b101), 3.into());
+            assert_eq!(get(&memory, 0b111), 4.into());
+        }
+
+        #[test]
+        fn fail_to_resolve_root() {
+            let memory = create_memory();
+
+            assert_eq!(memory.resolve(0), Err(Error::InvalidRegister));
+            assert_eq!(memory.resolve(1), Err(Error::InvalidRegister));
+        }
+
+        #[test]
+        fn fail_to_resolve_path_through_number() {
+            assert_eq!(create_memory().resolve(0b1000), Err(Error::InvalidRegister));
+        }
+
+        #[test]
+        fn fail_to_resolve_without_root() {
+            let memory =
+                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
+
+            assert_eq!(memory.resolve(0b10), Err(Error::InvalidRegister));
+        }
+    }
+
+    mod stack {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn create_memory() -> Memory<Value64, [Value64; HEAP_SIZE]> {
+            let mut memory =
+                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
+
+            let root = memory
+                .allocate(Default::default(), Default::default())
+                .unwrap();
+            memory.set_root(root.into());
+
+            memory
+        }
+
+        #[test]
+        fn push_and_pop() {
+            let mut memory = create_memory();
+
+            memory.push(0b11, 1.into()).unwrap();
+            memory.push(0b11, 2.into()).unwrap();
+
+            assert_eq!(memory.pop(0b11), Ok(2.into()));
+            assert_eq!(memory.pop(0b11), Ok(1.into()));
+            assert_eq!(memory.get(memory.resolve(0b11).unwrap()), Ok(0.into()));
+        }
+
+        #[test]
+        fn peek() {
+            let mut memory = create_memory();
+
+            memory.push(0b11, 1.into()).unwrap();
+            memory.push(0b11, 2.into()).unwrap();
+
+            for (position, value) in [(0, 2), (1, 1)] {
+                let index = memory.peek(0b11, position).unwrap();
+
+                assert_eq!(memory.get(index), Ok(value.into()));
+            }
+
+            assert_eq!(memory.peek(0b11, 2), Err(Error::ConsExpected));
+            assert_eq!(memory.pop(0b11), Ok(2.into()));
+        }
+
+        #[test]
+        fn fail_to_pop_empty_list() {
+            assert_eq!(create_memory().pop(0b11), Err(Error::ConsExpected));
+        }
+
+        #[test]
+        fn fail_to_push_to_invalid_register() {
+            assert_eq!(
+                create_memory().push(0b1, 0.into()),
+                Err(Error::InvalidRegister)
+            );
+        }
+    }
+
     mod allocation {
         use super::*;
+        use pretty_assertions::assert_eq;
 
         #[test]
         fn allocate_cons_cell() {
@@ -301,62 +739,500 @@ mod tests {
             assert_equal_values(&memory, x.into(), y.into());
             assert_free_list(&memory, 6);
         }
+
+        fn assert_list(
+            memory: &Memory<Value64, [Value64; HEAP_SIZE]>,
+            mut value: Value64,
+            length: usize,
+        ) {
+            for index in (0..length).rev() {
+                let cons = value.to_cons().unwrap();
+
+                assert_eq!(memory.get(cons.index()).unwrap(), (index as i64).into());
+
+                value = memory.get(cons.index() + 1).unwrap();
+            }
+
+            assert_eq!(value, Default::default());
+        }
+
+        #[test]
+        fn keep_arguments_alive() {
+            let mut memory =
+                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
+
+            let x = memory.allocate(1.into(), 2.into()).unwrap();
+            let y = memory.allocate(x.into(), 3.into()).unwrap();
+            let z = memory.allocate(4.into(), y.into()).unwrap();
+
+            for (index, value) in [
+                (z.index(), 4.into()),
+                (z.index() + 1, y.into()),
+                (y.index(), x.into()),
+                (y.index() + 1, 3.into()),
+                (x.index(), 1.into()),
+                (x.index() + 1, 2.into()),
+            ] {
+                assert_eq!(memory.get(index), Ok(value));
+            }
+
+            assert_eq!(
+                [x.index(), y.index(), z.index()]
+                    .into_iter()
+                    .collect::<HashSet<_>>()
+                    .len(),
+                3
+            );
+        }
+
+        #[test]
+        fn keep_list_alive_while_collecting_garbages() {
+            const LENGTH: usize = HEAP_SIZE / 2 - 8;
+
+            let mut memory =
+                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
+
+            for _ in 0..4 {
+                let mut list = Value64::default();
+
+                for index in 0..LENGTH {
+                    list = memory.allocate((index as i64).into(), list).unwrap().into();
+                }
+
+                assert_list(&memory, list, LENGTH);
+            }
+        }
+
+        #[test]
+        fn keep_nested_cars_alive_while_collecting_garbages() {
+            const LENGTH: usize = HEAP_SIZE / 2 - 8;
+
+            let mut memory =
+                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
+
+            for _ in 0..4 {
+                let mut value = Value64::default();
+
+                for index in 0..LENGTH {
+                    value = memory
+                        .allocate(value, (index as i64).into())
+                        .unwrap()
+                        .into();
+                }
+
+                for index in (0..LENGTH).rev() {
+                    let cons = value.to_cons().unwrap();
+
+                    assert_eq!(memory.get(cons.index() + 1), Ok((index as i64).into()));
+
+                    value = memory.get(cons.index()).unwrap();
+                }
+
+                assert_eq!(value, Default::default());
+            }
+        }
+    }
+
+    mod roots {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        fn create_memory() -> Memory<Value64, [Value64; HEAP_SIZE]> {
+            Memory::new([Default::default(); _]).unwrap()
+        }
+
+        #[test]
+        fn keep_values_on_root_stack() {
+            let mut memory = create_memory();
+
+            let x = memory.allocate(1.into(), 2.into()).unwrap();
+            memory.push_root(x.into()).unwrap();
+            let y = memory.allocate(x.into(), 3.into()).unwrap();
+            memory.push_root(y.into()).unwrap();
+            memory.collect_garbages().unwrap();
+
+            assert_free_list(&memory, 4);
+            assert_eq!(memory.get(x.index()), Ok(1.into()));
+            assert_eq!(memory.get(y.index()), Ok(x.into()));
+
+            assert_eq!(memory.pop_root(), Ok(y.into()));
+            memory.collect_garbages().unwrap();
+
+            assert_free_list(&memory, 2);
+
+            assert_eq!(memory.pop_root(), Ok(x.into()));
+            memory.collect_garbages().unwrap();
+
+            assert_free_list(&memory, 0);
+        }
+
+        #[test]
+        fn fail_to_pop_empty_root_stack() {
+            assert_eq!(create_memory().pop_root(), Err(Error::ConsExpected));
+        }
+
+        #[test]
+        fn keep_values_in_global_slots() {
+            let mut memory = create_memory();
+
+            let x = memory.allocate(1.into(), 2.into()).unwrap();
+            memory.set_global(2, x.into()).unwrap();
+            memory.collect_garbages().unwrap();
+
+            assert_free_list(&memory, 4);
+            assert_eq!(memory.global(0), Ok(Default::default()));
+            assert_eq!(memory.global(2), Ok(x.into()));
+            assert_eq!(memory.global(3), Ok(Default::default()));
+            assert_eq!(memory.get(x.index()), Ok(1.into()));
+
+            let y = memory.allocate(3.into(), 4.into()).unwrap();
+            memory.set_global(0, y.into()).unwrap();
+            memory.set_global(2, Default::default()).unwrap();
+            memory.collect_garbages().unwrap();
+
+            assert_free_list(&memory, 4);
+            assert_eq!(memory.global(0), Ok(y.into()));
+            assert_eq!(memory.global(2), Ok(Default::default()));
+            assert_eq!(memory.get(y.index()), Ok(3.into()));
+        }
+
+        #[test]
+        fn keep_global_slots_while_extending() {
+            let mut memory = create_memory();
+
+            for slot in 0..8 {
+                memory.set_global(slot, (slot as i64).into()).unwrap();
+            }
+
+            for slot in 0..8 {
+                assert_eq!(memory.global(slot), Ok((slot as i64).into()));
+            }
+        }
+
+        #[test]
+        fn fail_to_set_last_global_slot() {
+            let mut memory = create_memory();
+            memory.set_global(0, 42.into()).unwrap();
+
+            assert_eq!(memory.global(usize::MAX), Ok(Default::default()));
+            assert_eq!(
+                memory.set_global(usize::MAX, 1.into()),
+                Err(Error::OutOfMemory)
+            );
+            assert_eq!(memory.root_count(), 0);
+            assert_eq!(memory.global(0), Ok(42.into()));
+        }
+    }
+
+    mod reserve {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        const REGISTER: usize = 0b10;
+
+        fn create_memory() -> Memory<Value64, [Value64; HEAP_SIZE]> {
+            let mut memory =
+                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
+
+            let root = memory
+                .allocate(Default::default(), Default::default())
+                .unwrap();
+            memory.set_root(root.into());
+
+            memory
+        }
+
+        fn fill(memory: &mut Memory<Value64, [Value64; HEAP_SIZE]>) -> usize {
+            let mut count = 0;
+
+            loop {
+                match memory.push(REGISTER, 0.into()) {
+                    Ok(()) => count += 1,
+                    Err(error) => {
+                        assert_eq!(error, Error::OutOfMemory);
+                        return count;
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn fail_to_allocate_out_of_memory() {
+            let mut memory = create_memory();
+
+            assert_eq!(fill(&mut memory), HEAP_SIZE / 2 - 1);
+            assert_eq!(
+                memory.allocate(Default::default(), Default::default()),
+                Err(Error::OutOfMemory)
+            );
+            assert_eq!(memory.pop(REGISTER), Ok(0.into()));
+            assert!(memory.push(REGISTER, 0.into()).is_ok());
+        }
+
+        #[test]
+        fn allocate_from_reserve() {
+            const RESERVE: usize = 4;
+
+            let mut memory = create_memory();
+            memory.set_reserve(RESERVE);
+
+            assert_eq!(memory.reserve(), RESERVE);
+            assert_eq!(fill(&mut memory), HEAP_SIZE / 2 - 1 - RESERVE);
+            assert_eq!(fill(&mut memory), RESERVE);
+        }
+
+        #[test]
+        fn keep_back_reserve_again() {
+            const RESERVE: usize = 4;
+
+            let mut memory = create_memory();
+            memory.set_reserve(RESERVE);
+
+            fill(&mut memory);
+            fill(&mut memory);
+
+            let index = memory.resolve(REGISTER).unwrap();
+            memory.set(index, Default::default()).unwrap();
+
+            assert_eq!(fill(&mut memory), HEAP_SIZE / 2 - 1 - RESERVE);
+        }
+    }
+
+    mod copying {
+        use super::*;
+        use crate::Semispace;
+        use pretty_assertions::assert_eq;
+
+        type CopyingMemory = Memory<Value64, Semispace<[Value64; HEAP_SIZE]>>;
+
+        fn create_memory() -> CopyingMemory {
+            Memory::new(Semispace::new([Default::default(); _])).unwrap()
+        }
+
+        #[test]
+        fn allocate_in_order() {
+            let mut memory = create_memory();
+
+            let x = memory.allocate(1.into(), 2.into()).unwrap();
+            let y = memory.allocate_unchecked(3.into(), 4.into()).unwrap();
+            let z = memory.allocate_unchecked(5.into(), 6.into()).unwrap();
+
+            assert_eq!(y.index(), x.index() + 2);
+            assert_eq!(z.index(), y.index() + 2);
+        }
+
+        #[test]
+        fn compact_live_conses() {
+            let mut memory = create_memory();
+
+            let x = memory.allocate(1.into(), 2.into()).unwrap();
+            memory.allocate_unchecked(3.into(), 4.into()).unwrap();
+            let y = memory.allocate_unchecked(5.into(), x.into()).unwrap();
+            memory.allocate_unchecked(7.into(), 8.into()).unwrap();
+            memory.set_root(y.into());
+
+            memory.collect_garbages().unwrap();
+
+            let y = Cons::<Value64>::from(memory.root());
+            let x = Cons::<Value64>::from(memory.get(y.index() + 1).unwrap());
+
+            assert_eq!(y.index(), memory.space);
+            assert_eq!(x.index(), y.index() + 2);
+            assert_eq!(memory.get(x.index()), Ok(1.into()));
+            assert_eq!(memory.get(y.index()), Ok(5.into()));
+            assert_eq!(
+                memory
+                    .allocate_unchecked(9.into(), 10.into())
+                    .unwrap()
+                    .index(),
+                x.index() + 2
+            );
+        }
+
+        #[test]
+        fn flip_semispaces() {
+            let mut memory = create_memory();
+            let cons = memory.allocate(1.into(), 2.into()).unwrap();
+            memory.set_root(cons.into());
+            let space = memory.space;
+
+            memory.collect_garbages().unwrap();
+
+            assert_ne!(memory.space, space);
+
+            memory.collect_garbages().unwrap();
+
+            assert_eq!(memory.space, space);
+            assert_eq!(memory.get(Cons::from(memory.root()).index()), Ok(1.into()));
+        }
+
+        #[test]
+        fn keep_list_alive_while_copying() {
+            const LENGTH: usize = HEAP_SIZE / 4 - 8;
+
+            let mut memory = create_memory();
+
+            for _ in 0..4 {
+                let mut list = Value64::default();
+
+                for index in 0..LENGTH {
+                    list = memory.allocate((index as i64).into(), list).unwrap().into();
+                }
+
+                for index in (0..LENGTH).rev() {
+                    let cons = list.to_cons().unwrap();
+
+                    assert_eq!(memory.get(cons.index()), Ok((index as i64).into()));
+
+                    list = memory.get(cons.index() + 1).unwrap();
+                }
+
+                assert_eq!(list, Default::default());
+            }
+        }
+
+        #[test]
+        fn push_while_copying() {
+            const REGISTER: usize = 0b11;
+
+            let mut memory = create_memory();
+            let root = memory
+                .allocate(Default::default(), Default::default())
+                .unwrap();
+            memory.set_root(root.into());
+
+            for index in 0..HEAP_SIZE / 4 - 1 {
+                memory.push(REGISTER, (index as i64).into()).unwrap();
+                memory.collect_garbages().unwrap();
+            }
+
+            assert_eq!(memory.push(REGISTER, 0.into()), Err(Error::OutOfMemory));
+
+            for index in (0..HEAP_SIZE / 4 - 1).rev() {
+                assert_eq!(memory.pop(REGISTER), Ok((index as i64).into()));
+            }
+        }
     }
 
     mod garbage_collection {
         use super::*;
+        use crate::Semispace;
         use pretty_assertions::assert_eq;
+        use std::collections::HashMap;
+
+        // Defines tests running cases on mark-sweep and copying heaps.
+        macro_rules! test_heaps {
+            ($($name:ident),* $(,)?) => {
+                mod mark_sweep {
+                    use super::*;
+
+                    $(
+                        #[test]
+                        fn $name() {
+                            super::$name(Memory::<Value64, [Value64; HEAP_SIZE]>::new(
+                                [Default::default(); _],
+                            ).unwrap());
+                        }
+                    )*
+                }
 
-        fn assert_value<V: Value + Hash, const N: usize>(
-            memory: &Memory<V, [V; N]>,
-            other_memory: &Memory<V, [V; N]>,
-            x: V,
+                mod copying {
+                    use super::*;
+
+                    $(
+                        #[test]
+                        fn $name() {
+                            super::$name(Memory::<Value64, Semispace<[Value64; HEAP_SIZE]>>::new(
+                                Semispace::new([Default::default(); _]),
+                            ).unwrap());
+                        }
+                    )*
+                }
+            };
+        }
+
+        test_heaps!(
+            keep_cons,
+            collect_cons,
+            keep_two_cons_cells,
+            collect_two_cons_cells,
+            keep_three_cons_cells,
+            collect_three_cons_cells,
+            keep_tagged_cons_in_car,
+            keep_tagged_cons_in_cdr,
+            keep_tagged_cons_list,
+            keep_recursive_cons_in_car,
+            collect_recursive_cons_in_car,
+            keep_recursive_cons_in_cdr,
+            collect_recursive_cons_in_cdr,
+            keep_shared_cons,
+        );
+
+        // Asserts that a root has the same structure as one in an old
+        // memory while conses may be moved.
+        fn assert_root<V: Value + Hash, H: Heap<V>>(
+            memory: &Memory<V, H>,
+            old_memory: &Memory<V, H>,
         ) {
             let mut values = Default::default();
 
-            assert_recursive_value(&mut values, memory, other_memory, x)
+            assert_recursive_value(
+                &mut values,
+                memory,
+                old_memory,
+                memory.root(),
+                old_memory.root(),
+            )
         }
 
-        fn assert_recursive_value<V: Value + Hash, const N: usize>(
-            values: &mut HashSet<V>,
-            memory: &Memory<V, [V; N]>,
-            other_memory: &Memory<V, [V; N]>,
+        fn assert_recursive_value<V: Value + Hash, H: Heap<V>>(
+            values: &mut HashMap<V, V>,
+            memory: &Memory<V, H>,
+            old_memory: &Memory<V, H>,
             x: V,
+            y: V,
         ) {
-            if let Ok(x) = x.to_cons()
-                && values.insert(x.into())
-            {
-                for field in [0, 1] {
-                    let index = x.index() + field;
-                    let value = memory.get(index).unwrap();
+            assert_eq!(x.is_pointer(), y.is_pointer());
 
-                    assert_eq!(value, other_memory.get(index).unwrap());
+            let (Ok(x), Ok(y)) = (x.to_cons(), y.to_cons()) else {
+                assert_eq!(x, y);
+                return;
+            };
 
-                    assert_recursive_value(values, memory, other_memory, value);
-                }
+            assert_eq!(x.tag(), y.tag());
+
+            let (x, y) = (x.set_tag(0), y.set_tag(0));
+
+            if let Some(&value) = values.get(&y.into()) {
+                assert_eq!(value, x.into());
+                return;
             }
-        }
 
-        #[test]
-        fn keep_cons() {
-            let mut memory =
-                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
+            values.insert(y.into(), x.into());
+
+            for field in [0, 1] {
+                assert_recursive_value(
+                    values,
+                    memory,
+                    old_memory,
+                    memory.get(x.index() + field).unwrap(),
+                    old_memory.get(y.index() + field).unwrap(),
+                );
+            }
+        }
 
+        fn keep_cons<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
             let cons = memory.allocate(1.into(), 2.into()).unwrap();
             memory.set_root(cons.into());
 
             let old_memory = memory.clone();
             memory.collect_garbages().unwrap();
 
-            assert_value(&memory, &old_memory, cons.into());
+            assert_root(&memory, &old_memory);
             assert_free_list(&memory, 1);
         }
 
-        #[test]
-        fn collect_cons() {
-            let mut memory =
-                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
-
+        fn collect_cons<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
             memory.allocate(1.into(), 2.into()).unwrap();
 
             memory.collect_garbages().unwrap();
@@ -364,11 +1240,7 @@ mod tests {
             assert_free_list(&memory, 0);
         }
 
-        #[test]
-        fn keep_two_cons_cells() {
-            let mut memory =
-                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
-
+        fn keep_two_cons_cells<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
             let cons = memory.allocate(1.into(), 2.into()).unwrap();
             let cons = memory.allocate_unchecked(3.into(), cons.into()).unwrap();
             memory.set_root(cons.into());
@@ -376,15 +1248,11 @@ mod tests {
             let old_memory = memory.clone();
             memory.collect_garbages().unwrap();
 
-            assert_value(&memory, &old_memory, cons.into());
+            assert_root(&memory, &old_memory);
             assert_free_list(&memory, 2);
         }
 
-        #[test]
-        fn collect_two_cons_cells() {
-            let mut memory =
-                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
-
+        fn collect_two_cons_cells<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
             let cons = memory.allocate(1.into(), 2.into()).unwrap();
             memory.allocate(3.into(), cons.into()).unwrap();
 
@@ -393,11 +1261,7 @@ mod tests {
             assert_free_list(&memory, 0);
         }
 
-        #[test]
-        fn keep_three_cons_cells() {
-            let mut memory =
-                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
-
+        fn keep_three_cons_cells<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
             let car = memory.allocate(1.into(), 2.into()).unwrap();
             let cdr = memory.allocate_unchecked(3.into(), 4.into()).unwrap();
             let cons = memory.allocate_unchecked(car.into(), cdr.into()).unwrap();
@@ -406,29 +1270,66 @@ mod tests {
             let old_memory = memory.clone();
             memory.collect_garbages().unwrap();
 
-            assert_value(&memory, &old_memory, cons.into());
+            assert_root(&memory, &old_memory);
             assert_free_list(&memory, 3);
         }
 
-        #[test]
-        fn collect_three_cons_cells() {
-            let mut memory =
-                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
-
+        fn collect_three_cons_cells<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
             let car = memory.allocate(1.into(), 2.into()).unwrap();
-            let cdr = memory.allocate(3.into(), 4.into()).unwrap();
-            memory.allocate(car.into(), cdr.into()).unwrap();
+            let cdr = memory.allocate_unchecked(3.into(), 4.into()).unwrap();
+            memory.allocate_unchecked(car.into(), cdr.into()).unwrap();
 
             memory.collect_garbages().unwrap();
 
             assert_free_list(&memory, 0);
         }
 
-        #[test]
-        fn keep_recursive_cons_in_car() {
-            let mut memory =
-                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
+        fn keep_tagged_cons_in_car<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
+            let cons = memory.allocate(1.into(), 2.into()).unwrap();
+            let cons = memory
+                .allocate_unchecked(cons.set_tag(42).into(), 3.into())
+                .unwrap();
+            memory.set_root(cons.into());
+
+            let old_memory = memory.clone();
+            memory.collect_garbages().unwrap();
+
+            assert_root(&memory, &old_memory);
+            assert_free_list(&memory, 2);
+        }
+
+        fn keep_tagged_cons_in_cdr<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
+            let cons = memory.allocate(1.into(), 2.into()).unwrap();
+            let cons = memory
+                .allocate_unchecked(3.into(), cons.set_tag(42).into())
+                .unwrap();
+            memory.set_root(cons.into());
+
+            let old_memory = memory.clone();
+            memory.collect_garbages().unwrap();
+
+            assert_root(&memory, &old_memory);
+            assert_free_list(&memory, 2);
+        }
+
+        fn keep_tagged_cons_list<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
+            let cons = memory.allocate(1.into(), 2.into()).unwrap();
+            let cons = memory
+                .allocate_unchecked(3.into(), cons.set_tag(42).into())
+                .unwrap();
+            let cons = memory
+                .allocate_unchecked(4.into(), cons.set_tag(7).into())
+                .unwrap();
+            memory.set_root(cons.set_tag(13).into());
+
+            let old_memory = memory.clone();
+            memory.collect_garbages().unwrap();
 
+            assert_root(&memory, &old_memory);
+            assert_free_list(&memory, 3);
+        }
+
+        fn keep_recursive_cons_in_car<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
             let cons = memory.allocate(Default::default(), 42.into()).unwrap();
             memory.set(cons.index(), cons.into()).unwrap();
             memory.set_root(cons.into());
@@ -436,15 +1337,11 @@ mod tests {
             let old_memory = memory.clone();
             memory.collect_garbages().unwrap();
 
-            assert_value(&memory, &old_memory, cons.into());
+            assert_root(&memory, &old_memory);
             assert_free_list(&memory, 1);
         }
 
-        #[test]
-        fn collect_recursive_cons_in_car() {
-            let mut memory =
-                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
-
+        fn collect_recursive_cons_in_car<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
             let cons = memory.allocate(Default::default(), 42.into()).unwrap();
             memory.set(cons.index(), cons.into()).unwrap();
 
@@ -453,11 +1350,7 @@ mod tests {
             assert_free_list(&memory, 0);
         }
 
-        #[test]
-        fn keep_recursive_cons_in_cdr() {
-            let mut memory =
-                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
-
+        fn keep_recursive_cons_in_cdr<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
             let cons = memory.allocate(42.into(), Default::default()).unwrap();
             memory.set(cons.index() + 1, cons.into()).unwrap();
             memory.set_root(cons.into());
@@ -465,15 +1358,11 @@ mod tests {
             let old_memory = memory.clone();
             memory.collect_garbages().unwrap();
 
-            assert_value(&memory, &old_memory, cons.into());
+            assert_root(&memory, &old_memory);
             assert_free_list(&memory, 1);
         }
 
-        #[test]
-        fn collect_recursive_cons_in_cdr() {
-            let mut memory =
-                Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
-
+        fn collect_recursive_cons_in_cdr<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
             let cons = memory.allocate(42.into(), Default::default()).unwrap();
             memory.set(cons.index() + 1, cons.into()).unwrap();
 
@@ -481,5 +1370,19 @@ mod tests {
 
             assert_free_list(&memory, 0);
         }
+
+        fn keep_shared_cons<H: Heap<Value64> + Clone>(mut memory: Memory<Value64, H>) {
+            let cons = memory.allocate(1.into(), 2.into()).unwrap();
+            let cons = memory
+                .allocate_unchecked(cons.into(), cons.set_tag(42).into())
+                .unwrap();
+            memory.set_root(cons.into());
+
+            let old_memory = memory.clone();
+            memory.collect_garbages().unwrap();
+
+            assert_root(&memory, &old_memory);
+            assert_free_list(&memory, 2);
+        }
     }
 }
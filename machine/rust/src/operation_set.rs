@@ -1,7 +1,30 @@
+mod arithmetic;
+mod combined;
+#[cfg(all(feature = "std", unix))]
+mod file_system;
+mod host;
+#[cfg(feature = "std")]
+mod io;
+mod list;
+#[cfg(feature = "alloc")]
+mod registry;
 mod void;
 
 use crate::Memory;
-pub use void::VoidOperationSet;
+pub use arithmetic::{ArithmeticError, ArithmeticOperation, ArithmeticOperationSet};
+pub use combined::{CombinedError, CombinedOperationSet};
+use core::task::{Context, Poll};
+#[cfg(all(feature = "std", unix))]
+pub use file_system::{FileMode, FileSystemError, FileSystemOperation, FileSystemOperationSet};
+#[cfg(feature = "std")]
+pub use host::SystemHost;
+pub use host::{Host, HostError, HostOperation, HostOperationSet, SeededHost};
+#[cfg(feature = "std")]
+pub use io::{IoError, IoOperation, IoOperationSet};
+pub use list::{ListError, ListOperation, ListOperationSet};
+#[cfg(feature = "alloc")]
+pub use registry::{RegistryError, RegistryOperationSet};
+pub use void::{VoidError, VoidOperationSet};
 
 /// An operation set.
 pub trait OperationSet<V, H> {
@@ -10,4 +33,19 @@ pub trait OperationSet<V, H> {
 
     /// Runs an operation.
     fn operate(&mut self, memory: &mut Memory<V, H>, code: usize) -> Result<(), Self::Error>;
+
+    /// Polls an operation.
+    ///
+    /// An operation returns `Poll::Pending` to suspend a machine until a host
+    /// completes it. Then, the operation is polled again with the same code
+    /// when the machine resumes. By default, it runs an operation
+    /// synchronously.
+    fn poll_operate(
+        &mut self,
+        memory: &mut Memory<V, H>,
+        code: usize,
+        _context: &mut Context,
+    ) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(self.operate(memory, code))
+    }
 }
@@ -0,0 +1,361 @@
+mod error;
+
+pub use self::error::ArithmeticError;
+use crate::{Error, Heap, Integer, Memory, OperationSet, Value};
+
+/// An arithmetic operation.
+///
+/// A code of an operation is its discriminant.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ArithmeticOperation {
+    /// Adds numbers.
+    Add,
+    /// Subtracts numbers.
+    Subtract,
+    /// Multiplies numbers.
+    Multiply,
+    /// Divides numbers rounding toward zero.
+    Divide,
+    /// Calculates a remainder of division rounding toward zero.
+    Remainder,
+    /// Negates a number.
+    Negate,
+    /// Checks if numbers are equal.
+    Equal,
+    /// Checks if a number is less than another.
+    Less,
+    /// Checks if a number is less than or equal to another.
+    LessEqual,
+    /// Checks if a number is greater than another.
+    Greater,
+    /// Checks if a number is greater than or equal to another.
+    GreaterEqual,
+}
+
+impl ArithmeticOperation {
+    const ALL: &[Self] = &[
+        Self::Add,
+        Self::Subtract,
+        Self::Multiply,
+        Self::Divide,
+        Self::Remainder,
+        Self::Negate,
+        Self::Equal,
+        Self::Less,
+        Self::LessEqual,
+        Self::Greater,
+        Self::GreaterEqual,
+    ];
+
+    /// A number of operations.
+    pub const COUNT: usize = Self::ALL.len();
+
+    /// Decodes an operation from a code.
+    pub fn from_code(code: usize) -> Option<Self> {
+        Self::ALL.get(code).copied()
+    }
+}
+
+/// An arithmetic operation set.
+///
+/// It pops arguments from and pushes results onto a list in a stack register.
+/// The last argument is on the top of a stack. Comparisons push `1` if they
+/// hold and `0` otherwise. Results out of the range of numbers in values are
+/// overflow errors. Arguments are validated before they are popped so that a
+/// failed operation leaves a stack unchanged.
+#[derive(Debug)]
+pub struct ArithmeticOperationSet {
+    stack: usize,
+}
+
+impl ArithmeticOperationSet {
+    /// Creates an operation set with a stack register address.
+    pub const fn new(stack: usize) -> Self {
+        Self { stack }
+    }
+
+    fn peek<V: Value, H: Heap<V>>(
+        &self,
+        memory: &Memory<V, H>,
+        position: usize,
+    ) -> Result<V::Number, Error> {
+        memory
+            .get(memory.peek(self.stack, position)?)?
+            .to_number()
+            .map_err(|_| Error::NumberExpected)
+    }
+
+    fn value<V: Value>(number: V::Number) -> Result<V, ArithmeticError> {
+        let value = V::from(number);
+
+        if value.to_number() == Ok(number) {
+            Ok(value)
+        } else {
+            Err(ArithmeticError::Overflow)
+        }
+    }
+}
+
+impl<V: Value, H: Heap<V>> OperationSet<V, H> for ArithmeticOperationSet {
+    type Error = ArithmeticError;
+
+    fn operate(&mut self, memory: &mut Memory<V, H>, code: usize) -> Result<(), Self::Error> {
+        let operation =
+            ArithmeticOperation::from_code(code).ok_or(ArithmeticError::InvalidOperation)?;
+
+        let zero = V::Number::default();
+        let unary = operation == ArithmeticOperation::Negate;
+        let y = self.peek(memory, 0)?;
+        let x = if unary { zero } else { self.peek(memory, 1)? };
+
+        let z = match operation {
+            ArithmeticOperation::Add => x.checked_add(y),
+            ArithmeticOperation::Subtract | ArithmeticOperation::Negate => x.checked_sub(y),
+            ArithmeticOperation::Multiply => x.checked_mul(y),
+            ArithmeticOperation::Divide | ArithmeticOperation::Remainder if y == zero => {
+                return Err(ArithmeticError::DivisionByZero);
+            }
+            ArithmeticOperation::Divide => x.checked_div(y),
+            ArithmeticOperation::Remainder => x.checked_rem(y),
+            ArithmeticOperation::Equal => Some(u8::from(x == y).into()),
+            ArithmeticOperation::Less => Some(u8::from(x < y).into()),
+            ArithmeticOperation::LessEqual => Some(u8::from(x <= y).into()),
+            ArithmeticOperation::Greater => Some(u8::from(x > y).into()),
+            ArithmeticOperation::GreaterEqual => Some(u8::from(x >= y).into()),
+        };
+
+        let value = Self::value(z.ok_or(ArithmeticError::Overflow)?)?;
+
+        // Replace the first argument with a result in place without
+        // allocation.
+        if !unary {
+            memory.pop(self.stack)?;
+        }
+
+        Ok(memory.set(memory.peek(self.stack, 0)?, value)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Machine, Value32, Value64, assemble};
+    use alloc::vec::Vec;
+    use pretty_assertions::assert_eq;
+
+    const HEAP_SIZE: usize = 1 << 8;
+    const STACK: usize = 0b11;
+
+    fn create_memory<V: Value>() -> Memory<V, [V; HEAP_SIZE]> {
+        let mut memory = Memory::new([Default::default(); _]).unwrap();
+        let root = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(root.into());
+
+        memory
+    }
+
+    fn operate<V: Value>(
+        operation: ArithmeticOperation,
+        arguments: &[V::Number],
+    ) -> Result<V::Number, ArithmeticError> {
+        let mut memory = create_memory::<V>();
+
+        for &argument in arguments {
+            memory.push(STACK, argument.into()).unwrap();
+        }
+
+        ArithmeticOperationSet::new(STACK).operate(&mut memory, operation as _)?;
+
+        let result = memory.pop(STACK).unwrap().to_number().unwrap();
+
+        assert_eq!(memory.pop(STACK), Err(Error::ConsExpected));
+
+        Ok(result)
+    }
+
+    #[test]
+    fn calculate() {
+        for (operation, arguments, result) in [
+            (ArithmeticOperation::Add, &[1, 2][..], 3),
+            (ArithmeticOperation::Subtract, &[1, 2], -1),
+            (ArithmeticOperation::Multiply, &[3, -4], -12),
+            (ArithmeticOperation::Divide, &[7, 2], 3),
+            (ArithmeticOperation::Divide, &[-7, 2], -3),
+            (ArithmeticOperation::Remainder, &[7, 2], 1),
+            (ArithmeticOperation::Remainder, &[-7, 2], -1),
+            (ArithmeticOperation::Negate, &[5], -5),
+        ] {
+            assert_eq!(
+                operate::<Value64>(operation, arguments),
+                Ok(result),
+                "{operation:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn compare() {
+        for (operation, results) in [
+            (ArithmeticOperation::Equal, [0, 1, 0]),
+            (ArithmeticOperation::Less, [1, 0, 0]),
+            (ArithmeticOperation::LessEqual, [1, 1, 0]),
+            (ArithmeticOperation::Greater, [0, 0, 1]),
+            (ArithmeticOperation::GreaterEqual, [0, 1, 1]),
+        ] {
+            for (arguments, result) in [[1, 2], [2, 2], [3, 2]].iter().zip(results) {
+                assert_eq!(
+                    operate::<Value64>(operation, arguments),
+                    Ok(result),
+                    "{operation:?} {arguments:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn fail_to_divide_by_zero() {
+        assert_eq!(
+            operate::<Value64>(ArithmeticOperation::Divide, &[1, 0]),
+            Err(ArithmeticError::DivisionByZero)
+        );
+        assert_eq!(
+            operate::<Value64>(ArithmeticOperation::Remainder, &[1, 0]),
+            Err(ArithmeticError::DivisionByZero)
+        );
+    }
+
+    #[test]
+    fn fail_to_overflow() {
+        const MAX: i32 = i32::MAX >> 2;
+        const MIN: i32 = i32::MIN >> 2;
+
+        for (operation, arguments) in [
+            (ArithmeticOperation::Add, &[MAX, 1][..]),
+            (ArithmeticOperation::Subtract, &[MIN, 1]),
+            (ArithmeticOperation::Multiply, &[MAX, 2]),
+            (ArithmeticOperation::Divide, &[MIN, -1]),
+            (ArithmeticOperation::Negate, &[MIN]),
+        ] {
+            assert_eq!(
+                operate::<Value32>(operation, arguments),
+                Err(ArithmeticError::Overflow),
+                "{operation:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn keep_arguments_on_failure() {
+        const MAX: i32 = i32::MAX >> 2;
+
+        for (operation, arguments, error) in [
+            (
+                ArithmeticOperation::Divide,
+                &[1, 0][..],
+                ArithmeticError::DivisionByZero,
+            ),
+            (
+                ArithmeticOperation::Add,
+                &[MAX, 1],
+                ArithmeticError::Overflow,
+            ),
+        ] {
+            let mut memory = create_memory::<Value32>();
+
+            for &argument in arguments {
+                memory.push(STACK, argument.into()).unwrap();
+            }
+
+            assert_eq!(
+                ArithmeticOperationSet::new(STACK).operate(&mut memory, operation as _),
+                Err(error)
+            );
+
+            for &argument in arguments.iter().rev() {
+                assert_eq!(memory.pop(STACK), Ok(argument.into()));
+            }
+
+            assert_eq!(memory.pop(STACK), Err(Error::ConsExpected));
+        }
+    }
+
+    #[test]
+    fn calculate_at_limits() {
+        const MAX: i32 = i32::MAX >> 2;
+        const MIN: i32 = i32::MIN >> 2;
+
+        assert_eq!(
+            operate::<Value32>(ArithmeticOperation::Add, &[MAX - 1, 1]),
+            Ok(MAX)
+        );
+        assert_eq!(
+            operate::<Value32>(ArithmeticOperation::Subtract, &[MIN + 1, 1]),
+            Ok(MIN)
+        );
+        assert_eq!(
+            operate::<Value32>(ArithmeticOperation::Remainder, &[MIN, -1]),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn fail_to_operate_on_missing_argument() {
+        assert_eq!(
+            operate::<Value64>(ArithmeticOperation::Add, &[1]),
+            Err(ArithmeticError::Machine(Error::ConsExpected))
+        );
+    }
+
+    #[test]
+    fn fail_to_operate_on_non_number() {
+        let mut memory = create_memory::<Value64>();
+        memory.push(STACK, 1.into()).unwrap();
+        let cons = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.push(STACK, cons.into()).unwrap();
+
+        assert_eq!(
+            ArithmeticOperationSet::new(STACK).operate(&mut memory, ArithmeticOperation::Add as _),
+            Err(ArithmeticError::Machine(Error::NumberExpected))
+        );
+    }
+
+    #[test]
+    fn fail_to_run_invalid_operation() {
+        let mut memory = create_memory::<Value64>();
+
+        assert_eq!(
+            ArithmeticOperationSet::new(STACK).operate(&mut memory, ArithmeticOperation::ALL.len()),
+            Err(ArithmeticError::InvalidOperation)
+        );
+    }
+
+    #[test]
+    fn run_program() {
+        let mut machine = Machine::<Value64, [Value64; HEAP_SIZE], 0b10, _>::new(
+            [Default::default(); _],
+            ArithmeticOperationSet::new(STACK),
+        )
+        .unwrap();
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(
+            "
+            cons 0b11 6
+            cons 0b11 7
+            operate 2 ; multiply
+            cons 0b11 2
+            operate 1 ; subtract
+            ",
+            &mut bytecode,
+        )
+        .unwrap();
+        let output = machine.evaluate(bytecode, STACK).unwrap();
+
+        assert_eq!(output.car().unwrap().to_number(), Ok(40));
+        assert_eq!(output.cdr().unwrap().to_number(), Ok(0));
+    }
+}
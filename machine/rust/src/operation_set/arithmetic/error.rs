@@ -0,0 +1,44 @@
+use crate::Error;
+use core::{
+    error,
+    fmt::{self, Display, Formatter},
+};
+
+/// An arithmetic error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ArithmeticError {
+    /// Division by zero.
+    DivisionByZero,
+    /// Invalid operation.
+    InvalidOperation,
+    /// A machine error.
+    Machine(Error),
+    /// Integer overflow.
+    Overflow,
+}
+
+impl error::Error for ArithmeticError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Machine(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ArithmeticError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::DivisionByZero => write!(formatter, "division by zero"),
+            Self::InvalidOperation => write!(formatter, "invalid operation"),
+            Self::Machine(_) => write!(formatter, "machine error"),
+            Self::Overflow => write!(formatter, "integer overflow"),
+        }
+    }
+}
+
+impl From<Error> for ArithmeticError {
+    fn from(error: Error) -> Self {
+        Self::Machine(error)
+    }
+}
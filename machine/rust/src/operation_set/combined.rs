@@ -0,0 +1,228 @@
+mod error;
+
+pub use self::error::CombinedError;
+use crate::{Memory, OperationSet};
+use core::task::{Context, Poll};
+
+/// An operation set combining two operation sets by code ranges.
+///
+/// Codes less than a boundary are routed to a first operation set as they are.
+/// The other codes are routed to a second operation set with the boundary
+/// subtracted. Combined operation sets can be nested to combine more
+/// operation sets.
+#[derive(Debug)]
+pub struct CombinedOperationSet<A, B> {
+    first: A,
+    second: B,
+    boundary: usize,
+}
+
+impl<A, B> CombinedOperationSet<A, B> {
+    /// Creates an operation set.
+    pub const fn new(first: A, second: B, boundary: usize) -> Self {
+        Self {
+            first,
+            second,
+            boundary,
+        }
+    }
+
+    /// Returns a first operation set.
+    pub const fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Returns a second operation set.
+    pub const fn second(&self) -> &B {
+        &self.second
+    }
+}
+
+impl<V, H, A: OperationSet<V, H>, B: OperationSet<V, H>> OperationSet<V, H>
+    for CombinedOperationSet<A, B>
+{
+    type Error = CombinedError<A::Error, B::Error>;
+
+    fn operate(&mut self, memory: &mut Memory<V, H>, code: usize) -> Result<(), Self::Error> {
+        if code < self.boundary {
+            self.first
+                .operate(memory, code)
+                .map_err(CombinedError::First)
+        } else {
+            self.second
+                .operate(memory, code - self.boundary)
+                .map_err(CombinedError::Second)
+        }
+    }
+
+    fn poll_operate(
+        &mut self,
+        memory: &mut Memory<V, H>,
+        code: usize,
+        context: &mut Context,
+    ) -> Poll<Result<(), Self::Error>> {
+        if code < self.boundary {
+            self.first
+                .poll_operate(memory, code, context)
+                .map_err(CombinedError::First)
+        } else {
+            self.second
+                .poll_operate(memory, code - self.boundary, context)
+                .map_err(CombinedError::Second)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ArithmeticError, ArithmeticOperation, ArithmeticOperationSet, Error, ListError,
+        ListOperation, ListOperationSet, Machine, Value64, assemble,
+    };
+    use alloc::vec::Vec;
+    use core::task::Waker;
+    use pretty_assertions::assert_eq;
+
+    const HEAP_SIZE: usize = 1 << 8;
+    const STACK: usize = 0b11;
+
+    #[derive(Debug, Default)]
+    struct FakeOperationSet {
+        codes: Vec<usize>,
+        pending: bool,
+    }
+
+    impl<V, H> OperationSet<V, H> for FakeOperationSet {
+        type Error = &'static str;
+
+        fn operate(&mut self, _memory: &mut Memory<V, H>, code: usize) -> Result<(), Self::Error> {
+            self.codes.push(code);
+
+            Ok(())
+        }
+
+        fn poll_operate(
+            &mut self,
+            memory: &mut Memory<V, H>,
+            code: usize,
+            _context: &mut Context,
+        ) -> Poll<Result<(), Self::Error>> {
+            if self.pending {
+                self.pending = false;
+                return Poll::Pending;
+            }
+
+            Poll::Ready(self.operate(memory, code))
+        }
+    }
+
+    fn create_memory() -> Memory<Value64, [Value64; HEAP_SIZE]> {
+        let mut memory = Memory::new([Default::default(); _]).unwrap();
+        let root = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(root.into());
+
+        memory
+    }
+
+    #[test]
+    fn route_codes() {
+        let mut memory = create_memory();
+        let mut operation_set = CombinedOperationSet::new(
+            FakeOperationSet::default(),
+            CombinedOperationSet::new(FakeOperationSet::default(), FakeOperationSet::default(), 2),
+            3,
+        );
+
+        for code in 0..7 {
+            operation_set.operate(&mut memory, code).unwrap();
+        }
+
+        assert_eq!(operation_set.first().codes, [0, 1, 2]);
+        assert_eq!(operation_set.second().first().codes, [0, 1]);
+        assert_eq!(operation_set.second().second().codes, [0, 1]);
+    }
+
+    #[test]
+    fn route_polls() {
+        let mut memory = create_memory();
+        let mut context = Context::from_waker(Waker::noop());
+        let mut operation_set = CombinedOperationSet::new(
+            FakeOperationSet::default(),
+            FakeOperationSet {
+                pending: true,
+                ..Default::default()
+            },
+            1,
+        );
+
+        for (code, poll) in [
+            (0, Poll::Ready(Ok(()))),
+            (1, Poll::Pending),
+            (1, Poll::Ready(Ok(()))),
+        ] {
+            assert_eq!(
+                operation_set.poll_operate(&mut memory, code, &mut context),
+                poll
+            );
+        }
+
+        assert_eq!(operation_set.first().codes, [0]);
+        assert_eq!(operation_set.second().codes, [0]);
+    }
+
+    #[test]
+    fn route_errors() {
+        let mut memory = create_memory();
+        let mut operation_set = CombinedOperationSet::new(
+            ArithmeticOperationSet::new(STACK),
+            ListOperationSet::new(STACK),
+            ArithmeticOperation::COUNT,
+        );
+
+        assert_eq!(
+            operation_set.operate(&mut memory, ArithmeticOperation::Add as _),
+            Err(CombinedError::First(ArithmeticError::Machine(
+                Error::ConsExpected
+            )))
+        );
+        assert_eq!(
+            operation_set.operate(
+                &mut memory,
+                ArithmeticOperation::COUNT + ListOperation::COUNT
+            ),
+            Err(CombinedError::Second(ListError::InvalidOperation))
+        );
+    }
+
+    #[test]
+    fn run_program() {
+        let mut machine = Machine::<Value64, [Value64; HEAP_SIZE], 0b10, _>::new(
+            [Default::default(); _],
+            CombinedOperationSet::new(
+                ArithmeticOperationSet::new(STACK),
+                ListOperationSet::new(STACK),
+                ArithmeticOperation::COUNT,
+            ),
+        )
+        .unwrap();
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(
+            "
+            cons 0b11 1
+            cons 0b11 2
+            operate 0 ; add
+            operate 16 ; null?
+            ",
+            &mut bytecode,
+        )
+        .unwrap();
+        let output = machine.evaluate(bytecode, STACK).unwrap();
+
+        assert_eq!(output.car().unwrap().to_number(), Ok(0));
+        assert_eq!(output.cdr().unwrap().to_number(), Ok(0));
+    }
+}
@@ -0,0 +1,33 @@
+use core::{
+    error,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+/// An error of a combined operation set.
+///
+/// It displays and chains an error of an underlying operation set as it is.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CombinedError<A, B> {
+    /// An error of a first operation set.
+    First(A),
+    /// An error of a second operation set.
+    Second(B),
+}
+
+impl<A: error::Error, B: error::Error> error::Error for CombinedError<A, B> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::First(error) => error.source(),
+            Self::Second(error) => error.source(),
+        }
+    }
+}
+
+impl<A: Display, B: Display> Display for CombinedError<A, B> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::First(error) => write!(formatter, "{error}"),
+            Self::Second(error) => write!(formatter, "{error}"),
+        }
+    }
+}
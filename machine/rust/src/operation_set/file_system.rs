@@ -0,0 +1,774 @@
+mod error;
+
+pub use self::error::FileSystemError;
+use super::io::{peek_byte, push_byte, read_byte};
+use crate::{Error, Heap, Integer, Memory, OperationSet, Value};
+use alloc::{ffi::CString, string::String, vec::Vec};
+use libc::{
+    O_APPEND, O_CLOEXEC, O_CREAT, O_DIRECTORY, O_NOFOLLOW, O_RDONLY, O_TRUNC, O_WRONLY, c_int,
+    c_uint,
+};
+use std::{
+    ffi::OsStr,
+    fs::{self, File},
+    io::{self, Write},
+    os::{
+        fd::{AsRawFd, FromRawFd},
+        unix::ffi::OsStrExt,
+    },
+    path::{Component, Path, PathBuf},
+};
+
+/// A file system operation.
+///
+/// A code of an operation is its discriminant.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FileSystemOperation {
+    /// Opens a file.
+    Open,
+    /// Reads a byte from a file.
+    Read,
+    /// Writes a byte to a file.
+    Write,
+    /// Closes a file.
+    Close,
+    /// Lists entries in a directory.
+    ListDirectory,
+}
+
+impl FileSystemOperation {
+    const ALL: &[Self] = &[
+        Self::Open,
+        Self::Read,
+        Self::Write,
+        Self::Close,
+        Self::ListDirectory,
+    ];
+
+    /// A number of operations.
+    pub const COUNT: usize = Self::ALL.len();
+
+    /// Decodes an operation from a code.
+    pub fn from_code(code: usize) -> Option<Self> {
+        Self::ALL.get(code).copied()
+    }
+}
+
+/// A file mode.
+///
+/// A number of a mode is its discriminant.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FileMode {
+    /// Reads a file.
+    Read,
+    /// Writes a file truncating or creating it.
+    Write,
+    /// Appends to a file creating it if it does not exist.
+    Append,
+}
+
+impl FileMode {
+    const ALL: &[Self] = &[Self::Read, Self::Write, Self::Append];
+
+    const fn flags(self) -> c_int {
+        match self {
+            Self::Read => O_RDONLY,
+            Self::Write => O_WRONLY | O_CREAT | O_TRUNC,
+            Self::Append => O_WRONLY | O_CREAT | O_APPEND,
+        }
+    }
+
+    // Opens a file at a path relative to a directory component by component
+    // without following any symbolic links as the path might be replaced
+    // after it is resolved.
+    fn open(self, directory: &Path, path: &Path) -> io::Result<File> {
+        let names = path
+            .components()
+            .map(Component::as_os_str)
+            .collect::<Vec<_>>();
+        let Some((name, directories)) = names.split_last() else {
+            return Err(io::ErrorKind::IsADirectory.into());
+        };
+        let mut directory = File::open(directory)?;
+
+        for name in directories {
+            directory = open_at(&directory, name, O_RDONLY | O_DIRECTORY)?;
+        }
+
+        open_at(&directory, name, self.flags())
+    }
+}
+
+// Opens a file in a directory without following a symbolic link.
+fn open_at(directory: &File, name: &OsStr, flags: c_int) -> io::Result<File> {
+    let name = CString::new(name.as_bytes())?;
+    // SAFETY: A directory descriptor and a name are alive during the call.
+    let descriptor = unsafe {
+        libc::openat(
+            directory.as_raw_fd(),
+            name.as_ptr(),
+            flags | O_NOFOLLOW | O_CLOEXEC,
+            0o666 as c_uint,
+        )
+    };
+
+    if descriptor < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: A descriptor is newly opened and owned by nothing else.
+    Ok(unsafe { File::from_raw_fd(descriptor) })
+}
+
+/// A file system operation set.
+///
+/// It pops arguments from and pushes results onto a list in a stack register.
+/// Paths and entry names are lists of bytes in UTF-8. Files are referred to
+/// by handles of numbers.
+///
+/// - `Open` pops a mode and a path, and pushes a handle.
+/// - `Read` pops a handle and pushes a byte or `-1` at the end of a file.
+/// - `Write` pops a handle and a byte.
+/// - `Close` pops a handle.
+/// - `ListDirectory` pops a path and pushes a list of sorted entry names.
+///
+/// Every path is relative to a sandbox directory. Paths escaping the sandbox
+/// through parent directories or symbolic links are rejected. Arguments are
+/// kept on the stack if an operation fails.
+#[derive(Debug)]
+pub struct FileSystemOperationSet {
+    root: PathBuf,
+    stack: usize,
+    files: Vec<Option<File>>,
+}
+
+impl FileSystemOperationSet {
+    /// Creates an operation set with a sandbox directory and a stack register
+    /// address.
+    pub fn new(root: impl AsRef<Path>, stack: usize) -> io::Result<Self> {
+        Ok(Self {
+            root: root.as_ref().canonicalize()?,
+            stack,
+            files: Vec::new(),
+        })
+    }
+
+    fn resolve(&self, path: &str) -> Result<PathBuf, FileSystemError> {
+        if Path::new(path)
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(FileSystemError::PathEscape);
+        }
+
+        let path = self.root.join(path);
+        let real = match path.canonicalize() {
+            Ok(path) => path,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+                    return Err(FileSystemError::InvalidPath);
+                };
+
+                // A dangling symbolic link can point outside a sandbox.
+                if path.symlink_metadata().is_ok() {
+                    return Err(FileSystemError::PathEscape);
+                }
+
+                parent.canonicalize()?.join(name)
+            }
+            Err(error) => return Err(error.into()),
+        };
+
+        if real.starts_with(&self.root) {
+            Ok(real)
+        } else {
+            Err(FileSystemError::PathEscape)
+        }
+    }
+
+    // Opens a resolved path in a sandbox. A symbolic link found at this point
+    // replaces a resolved file or directory.
+    fn open_path(&self, path: &Path, mode: FileMode) -> Result<File, FileSystemError> {
+        mode.open(
+            &self.root,
+            path.strip_prefix(&self.root)
+                .map_err(|_| FileSystemError::PathEscape)?,
+        )
+        .map_err(|error| {
+            if matches!(error.raw_os_error(), Some(libc::ELOOP | libc::ENOTDIR)) {
+                FileSystemError::PathEscape
+            } else {
+                error.into()
+            }
+        })
+    }
+
+    fn path<V: Value, H: Heap<V>>(
+        &self,
+        memory: &Memory<V, H>,
+        position: usize,
+    ) -> Result<PathBuf, FileSystemError> {
+        let mut value = memory.get(memory.peek(self.stack, position)?)?;
+        let mut bytes = Vec::new();
+
+        while let Ok(cons) = value.to_cons() {
+            if bytes.len() > memory.size() {
+                return Err(FileSystemError::InvalidPath);
+            }
+
+            let byte = memory
+                .get(cons.index())?
+                .to_number()
+                .map_err(|_| FileSystemError::InvalidPath)?;
+
+            if byte < Default::default() || byte > u8::MAX.into() {
+                return Err(FileSystemError::InvalidPath);
+            }
+
+            bytes.push(byte.to_usize() as u8);
+            value = memory.get(cons.index() + 1)?;
+        }
+
+        self.resolve(&String::from_utf8(bytes).map_err(|_| FileSystemError::InvalidPath)?)
+    }
+
+    fn handle<V: Value, H: Heap<V>>(
+        &self,
+        memory: &Memory<V, H>,
+        position: usize,
+    ) -> Result<usize, FileSystemError> {
+        let handle = memory
+            .get(memory.peek(self.stack, position)?)?
+            .to_number()
+            .map_err(|_| Error::NumberExpected)?;
+
+        if handle < Default::default() {
+            return Err(FileSystemError::InvalidHandle);
+        }
+
+        Ok(handle.to_usize())
+    }
+
+    fn file(&mut self, handle: usize) -> Result<&mut File, FileSystemError> {
+        self.files
+            .get_mut(handle)
+            .and_then(Option::as_mut)
+            .ok_or(FileSystemError::InvalidHandle)
+    }
+
+    fn open(&mut self, file: File) -> usize {
+        if let Some(handle) = self.files.iter().position(Option::is_none) {
+            self.files[handle] = Some(file);
+            handle
+        } else {
+            self.files.push(Some(file));
+            self.files.len() - 1
+        }
+    }
+
+    // Pushes a list of names keeping intermediate lists reachable from a stack.
+    fn push_names<V: Value, H: Heap<V>>(
+        &self,
+        memory: &mut Memory<V, H>,
+        names: &[String],
+    ) -> Result<(), Error> {
+        memory.push(self.stack, Default::default())?;
+
+        for name in names.iter().rev() {
+            memory.push(self.stack, Default::default())?;
+
+            for &byte in name.as_bytes().iter().rev() {
+                let cons = memory.allocate(
+                    V::Number::from(byte).into(),
+                    memory.get(memory.peek(self.stack, 0)?)?,
+                )?;
+                memory.set(memory.peek(self.stack, 0)?, cons.into())?;
+            }
+
+            let cons = memory.allocate(
+                memory.get(memory.peek(self.stack, 0)?)?,
+                memory.get(memory.peek(self.stack, 1)?)?,
+            )?;
+            memory.pop(self.stack)?;
+            memory.set(memory.peek(self.stack, 0)?, cons.into())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<V: Value, H: Heap<V>> OperationSet<V, H> for FileSystemOperationSet {
+    type Error = FileSystemError;
+
+    fn operate(&mut self, memory: &mut Memory<V, H>, code: usize) -> Result<(), Self::Error> {
+        match FileSystemOperation::from_code(code).ok_or(FileSystemError::InvalidOperation)? {
+            FileSystemOperation::Open => {
+                let mode = memory
+                    .get(memory.peek(self.stack, 0)?)?
+                    .to_number()
+                    .map_err(|_| Error::NumberExpected)?;
+                let mode = (mode >= Default::default())
+                    .then(|| FileMode::ALL.get(mode.to_usize()))
+                    .flatten()
+                    .ok_or(FileSystemError::InvalidMode)?;
+                let file = self.open_path(&self.path(memory, 1)?, *mode)?;
+                memory.pop(self.stack)?;
+                memory.pop(self.stack)?;
+                let handle = self.open(file);
+
+                memory.push(self.stack, V::Number::from_usize(handle).into())?;
+            }
+            FileSystemOperation::Read => {
+                let handle = self.handle(memory, 0)?;
+                let byte = read_byte(self.file(handle)?)?;
+                memory.pop(self.stack)?;
+
+                push_byte(memory, self.stack, byte)?;
+            }
+            FileSystemOperation::Write => {
+                let byte = peek_byte(memory, self.stack, 0)?.ok_or(FileSystemError::InvalidByte)?;
+                let handle = self.handle(memory, 1)?;
+
+                self.file(handle)?.write_all(&[byte])?;
+                memory.pop(self.stack)?;
+                memory.pop(self.stack)?;
+            }
+            FileSystemOperation::Close => {
+                let handle = self.handle(memory, 0)?;
+
+                self.file(handle)?;
+                self.files[handle] = None;
+                memory.pop(self.stack)?;
+            }
+            FileSystemOperation::ListDirectory => {
+                let mut names = fs::read_dir(self.path(memory, 0)?)?
+                    .map(|entry| Ok(entry?.file_name().to_string_lossy().into()))
+                    .collect::<Result<Vec<String>, io::Error>>()?;
+                names.sort();
+                memory.pop(self.stack)?;
+
+                self.push_names(memory, &names)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value64;
+    use alloc::{format, vec};
+    use pretty_assertions::assert_eq;
+    use std::{env, process};
+
+    const HEAP_SIZE: usize = 1 << 10;
+    const STACK: usize = 0b11;
+
+    type TestMemory = Memory<Value64, [Value64; HEAP_SIZE]>;
+
+    struct Sandbox {
+        path: PathBuf,
+    }
+
+    impl Sandbox {
+        fn new(name: &str) -> Self {
+            let path = env::temp_dir().join(format!("moco-{}-{name}", process::id()));
+
+            fs::remove_dir_all(&path).ok();
+            fs::create_dir_all(path.join("sandbox")).unwrap();
+
+            Self { path }
+        }
+
+        fn root(&self) -> PathBuf {
+            self.path.join("sandbox")
+        }
+    }
+
+    impl Drop for Sandbox {
+        fn drop(&mut self) {
+            fs::remove_dir_all(&self.path).ok();
+        }
+    }
+
+    fn create_memory() -> TestMemory {
+        let mut memory = Memory::new([Default::default(); _]).unwrap();
+        let root = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(root.into());
+
+        memory
+    }
+
+    fn push_path(memory: &mut TestMemory, path: &str) {
+        memory.push(STACK, Default::default()).unwrap();
+
+        for &byte in path.as_bytes().iter().rev() {
+            let index = memory
+                .get(memory.resolve(STACK).unwrap())
+                .unwrap()
+                .to_cons()
+                .unwrap()
+                .index();
+            let cons = memory
+                .allocate((byte as i64).into(), memory.get(index).unwrap())
+                .unwrap();
+            memory.set(index, cons.into()).unwrap();
+        }
+    }
+
+    fn operate(
+        operation_set: &mut FileSystemOperationSet,
+        memory: &mut TestMemory,
+        operation: FileSystemOperation,
+    ) -> Result<(), FileSystemError> {
+        operation_set.operate(memory, operation as _)
+    }
+
+    fn open(
+        operation_set: &mut FileSystemOperationSet,
+        memory: &mut TestMemory,
+        path: &str,
+        mode: FileMode,
+    ) -> Result<Value64, FileSystemError> {
+        push_path(memory, path);
+        memory.push(STACK, (mode as i64).into()).unwrap();
+        operate(operation_set, memory, FileSystemOperation::Open)?;
+
+        Ok(memory.pop(STACK).unwrap())
+    }
+
+    fn write(
+        operation_set: &mut FileSystemOperationSet,
+        memory: &mut TestMemory,
+        handle: Value64,
+        bytes: &[u8],
+    ) {
+        for &byte in bytes {
+            memory.push(STACK, handle).unwrap();
+            memory.push(STACK, (byte as i64).into()).unwrap();
+            operate(operation_set, memory, FileSystemOperation::Write).unwrap();
+        }
+    }
+
+    fn close(
+        operation_set: &mut FileSystemOperationSet,
+        memory: &mut TestMemory,
+        handle: Value64,
+    ) -> Result<(), FileSystemError> {
+        memory.push(STACK, handle).unwrap();
+        operate(operation_set, memory, FileSystemOperation::Close)
+    }
+
+    fn read_string(memory: &TestMemory, mut value: Value64) -> String {
+        let mut string = String::new();
+
+        while let Ok(cons) = value.to_cons() {
+            string.push(i64::from(memory.get(cons.index()).unwrap()) as u8 as char);
+            value = memory.get(cons.index() + 1).unwrap();
+        }
+
+        string
+    }
+
+    #[test]
+    fn write_and_read_file() {
+        let sandbox = Sandbox::new("write_and_read_file");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+
+        let handle = open(&mut operation_set, &mut memory, "foo", FileMode::Write).unwrap();
+        write(&mut operation_set, &mut memory, handle, b"ab");
+        close(&mut operation_set, &mut memory, handle).unwrap();
+
+        assert_eq!(fs::read(sandbox.root().join("foo")).unwrap(), b"ab");
+
+        let handle = open(&mut operation_set, &mut memory, "foo", FileMode::Read).unwrap();
+
+        for byte in [b'a' as i64, b'b' as _, -1] {
+            memory.push(STACK, handle).unwrap();
+            operate(&mut operation_set, &mut memory, FileSystemOperation::Read).unwrap();
+
+            assert_eq!(memory.pop(STACK), Ok(byte.into()));
+        }
+
+        close(&mut operation_set, &mut memory, handle).unwrap();
+        assert_eq!(memory.pop(STACK), Err(Error::ConsExpected));
+    }
+
+    #[test]
+    fn append_to_file() {
+        let sandbox = Sandbox::new("append_to_file");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+        fs::write(sandbox.root().join("foo"), b"a").unwrap();
+
+        let handle = open(&mut operation_set, &mut memory, "./foo", FileMode::Append).unwrap();
+        write(&mut operation_set, &mut memory, handle, b"b");
+        close(&mut operation_set, &mut memory, handle).unwrap();
+
+        assert_eq!(fs::read(sandbox.root().join("foo")).unwrap(), b"ab");
+    }
+
+    #[test]
+    fn reuse_handle() {
+        let sandbox = Sandbox::new("reuse_handle");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+
+        let foo = open(&mut operation_set, &mut memory, "foo", FileMode::Write).unwrap();
+        let bar = open(&mut operation_set, &mut memory, "bar", FileMode::Write).unwrap();
+        close(&mut operation_set, &mut memory, foo).unwrap();
+
+        assert_eq!(foo, 0.into());
+        assert_eq!(bar, 1.into());
+        assert_eq!(
+            open(&mut operation_set, &mut memory, "baz", FileMode::Write).unwrap(),
+            foo
+        );
+    }
+
+    #[test]
+    fn list_directory() {
+        let sandbox = Sandbox::new("list_directory");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+        fs::write(sandbox.root().join("foo"), b"").unwrap();
+        fs::write(sandbox.root().join("bar"), b"").unwrap();
+        fs::create_dir(sandbox.root().join("baz")).unwrap();
+        fs::write(sandbox.root().join("baz/qux"), b"").unwrap();
+
+        for (path, expected) in [("", vec!["bar", "baz", "foo"]), ("baz", vec!["qux"])] {
+            push_path(&mut memory, path);
+            operate(
+                &mut operation_set,
+                &mut memory,
+                FileSystemOperation::ListDirectory,
+            )
+            .unwrap();
+
+            let mut value = memory.pop(STACK).unwrap();
+            let mut names = Vec::new();
+
+            while let Ok(cons) = value.to_cons() {
+                names.push(read_string(&memory, memory.get(cons.index()).unwrap()));
+                value = memory.get(cons.index() + 1).unwrap();
+            }
+
+            assert_eq!(names, expected);
+        }
+    }
+
+    #[test]
+    fn refuse_escapes() {
+        let sandbox = Sandbox::new("refuse_escapes");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+        fs::write(sandbox.path.join("secret"), b"").unwrap();
+
+        for path in [
+            "../secret",
+            "foo/../../secret",
+            sandbox.path.join("secret").to_string_lossy().as_ref(),
+        ] {
+            assert!(
+                matches!(
+                    open(&mut operation_set, &mut memory, path, FileMode::Read),
+                    Err(FileSystemError::PathEscape)
+                ),
+                "{path}"
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuse_escape_through_symbolic_link() {
+        let sandbox = Sandbox::new("refuse_escape_through_symbolic_link");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+        fs::write(sandbox.path.join("secret"), b"").unwrap();
+        std::os::unix::fs::symlink(&sandbox.path, sandbox.root().join("link")).unwrap();
+
+        for mode in [FileMode::Read, FileMode::Write] {
+            assert!(matches!(
+                open(&mut operation_set, &mut memory, "link/secret", mode),
+                Err(FileSystemError::PathEscape)
+            ));
+        }
+        assert!(matches!(
+            open(&mut operation_set, &mut memory, "link/new", FileMode::Write),
+            Err(FileSystemError::PathEscape)
+        ));
+        assert!(!sandbox.path.join("new").exists());
+    }
+
+    #[test]
+    fn refuse_symbolic_link_replacing_resolved_path() {
+        let sandbox = Sandbox::new("refuse_symbolic_link_replacing_resolved_path");
+        let operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+        fs::write(sandbox.path.join("secret"), b"foo").unwrap();
+        std::os::unix::fs::symlink(&sandbox.path, sandbox.root().join("link")).unwrap();
+        std::os::unix::fs::symlink("../secret", sandbox.root().join("file")).unwrap();
+
+        for path in ["link/secret", "link/new", "file"] {
+            for mode in [FileMode::Read, FileMode::Write, FileMode::Append] {
+                assert!(
+                    matches!(
+                        operation_set.open_path(&operation_set.root.join(path), mode),
+                        Err(FileSystemError::PathEscape)
+                    ),
+                    "{path}"
+                );
+            }
+        }
+
+        assert_eq!(fs::read(sandbox.path.join("secret")).unwrap(), b"foo");
+        assert!(!sandbox.path.join("new").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuse_dangling_symbolic_link() {
+        let sandbox = Sandbox::new("refuse_dangling_symbolic_link");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+        std::os::unix::fs::symlink("../secret", sandbox.root().join("link")).unwrap();
+
+        for mode in [FileMode::Read, FileMode::Write, FileMode::Append] {
+            assert!(matches!(
+                open(&mut operation_set, &mut memory, "link", mode),
+                Err(FileSystemError::PathEscape)
+            ));
+        }
+        assert!(!sandbox.path.join("secret").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn refuse_symbolic_link_to_file() {
+        let sandbox = Sandbox::new("refuse_symbolic_link_to_file");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+        fs::write(sandbox.path.join("secret"), b"foo").unwrap();
+        std::os::unix::fs::symlink("../secret", sandbox.root().join("link")).unwrap();
+
+        for mode in [FileMode::Read, FileMode::Write, FileMode::Append] {
+            assert!(matches!(
+                open(&mut operation_set, &mut memory, "link", mode),
+                Err(FileSystemError::PathEscape)
+            ));
+        }
+        assert_eq!(fs::read(sandbox.path.join("secret")).unwrap(), b"foo");
+    }
+
+    #[test]
+    fn truncate_file() {
+        let sandbox = Sandbox::new("truncate_file");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+        fs::write(sandbox.root().join("foo"), b"abc").unwrap();
+
+        let handle = open(&mut operation_set, &mut memory, "foo", FileMode::Write).unwrap();
+        write(&mut operation_set, &mut memory, handle, b"d");
+        close(&mut operation_set, &mut memory, handle).unwrap();
+
+        assert_eq!(fs::read(sandbox.root().join("foo")).unwrap(), b"d");
+    }
+
+    #[test]
+    fn fail_to_use_invalid_handle() {
+        let sandbox = Sandbox::new("fail_to_use_invalid_handle");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+
+        let handle = open(&mut operation_set, &mut memory, "foo", FileMode::Write).unwrap();
+        close(&mut operation_set, &mut memory, handle).unwrap();
+
+        for handle in [handle, 42.into(), (-1).into()] {
+            assert!(matches!(
+                close(&mut operation_set, &mut memory, handle),
+                Err(FileSystemError::InvalidHandle)
+            ));
+            assert_eq!(memory.pop(STACK), Ok(handle));
+        }
+    }
+
+    #[test]
+    fn keep_arguments_on_failed_write() {
+        let sandbox = Sandbox::new("keep_arguments_on_failed_write");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+        let handle = open(&mut operation_set, &mut memory, "foo", FileMode::Write).unwrap();
+
+        for (handle, byte) in [(handle, 256), (42.into(), 0)] {
+            memory.push(STACK, handle).unwrap();
+            memory.push(STACK, byte.into()).unwrap();
+
+            assert!(matches!(
+                operate(&mut operation_set, &mut memory, FileSystemOperation::Write),
+                Err(FileSystemError::InvalidByte | FileSystemError::InvalidHandle)
+            ));
+            assert_eq!(memory.pop(STACK), Ok(byte.into()));
+            assert_eq!(memory.pop(STACK), Ok(handle));
+        }
+    }
+
+    #[test]
+    fn fail_to_open_with_invalid_mode() {
+        let sandbox = Sandbox::new("fail_to_open_with_invalid_mode");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+
+        for mode in [-1, FileMode::ALL.len() as i64] {
+            push_path(&mut memory, "foo");
+            memory.push(STACK, mode.into()).unwrap();
+
+            assert!(matches!(
+                operate(&mut operation_set, &mut memory, FileSystemOperation::Open),
+                Err(FileSystemError::InvalidMode)
+            ));
+            assert_eq!(memory.pop(STACK), Ok(mode.into()));
+            let path = memory.pop(STACK).unwrap();
+            assert_eq!(read_string(&memory, path), "foo");
+        }
+    }
+
+    #[test]
+    fn fail_to_open_invalid_path() {
+        let sandbox = Sandbox::new("fail_to_open_invalid_path");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+
+        memory.push(STACK, 0xff.into()).unwrap();
+        let index = memory
+            .get(memory.resolve(STACK).unwrap())
+            .unwrap()
+            .to_cons()
+            .unwrap()
+            .index();
+        let cons = memory.allocate(0xff.into(), Default::default()).unwrap();
+        memory.set(index, cons.into()).unwrap();
+        memory.push(STACK, (FileMode::Read as i64).into()).unwrap();
+
+        assert!(matches!(
+            operate(&mut operation_set, &mut memory, FileSystemOperation::Open),
+            Err(FileSystemError::InvalidPath)
+        ));
+    }
+
+    #[test]
+    fn fail_to_open_missing_file() {
+        let sandbox = Sandbox::new("fail_to_open_missing_file");
+        let mut memory = create_memory();
+        let mut operation_set = FileSystemOperationSet::new(sandbox.root(), STACK).unwrap();
+
+        assert!(matches!(
+            open(&mut operation_set, &mut memory, "foo", FileMode::Read),
+            Err(FileSystemError::Io(error)) if error.kind() == io::ErrorKind::NotFound
+        ));
+    }
+}
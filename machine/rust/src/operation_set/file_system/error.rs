@@ -0,0 +1,64 @@
+use crate::Error;
+use core::{
+    error,
+    fmt::{self, Display, Formatter},
+};
+use std::io;
+
+/// A file system error.
+#[derive(Debug)]
+pub enum FileSystemError {
+    /// Invalid byte.
+    InvalidByte,
+    /// Invalid file handle.
+    InvalidHandle,
+    /// Invalid file mode.
+    InvalidMode,
+    /// Invalid operation.
+    InvalidOperation,
+    /// Invalid path.
+    InvalidPath,
+    /// An I/O error of a file system.
+    Io(io::Error),
+    /// A machine error.
+    Machine(Error),
+    /// A path escaping a sandbox.
+    PathEscape,
+}
+
+impl error::Error for FileSystemError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Machine(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for FileSystemError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidByte => write!(formatter, "invalid byte"),
+            Self::InvalidHandle => write!(formatter, "invalid file handle"),
+            Self::InvalidMode => write!(formatter, "invalid file mode"),
+            Self::InvalidOperation => write!(formatter, "invalid operation"),
+            Self::InvalidPath => write!(formatter, "invalid path"),
+            Self::Io(_) => write!(formatter, "I/O error"),
+            Self::Machine(_) => write!(formatter, "machine error"),
+            Self::PathEscape => write!(formatter, "path escaping sandbox"),
+        }
+    }
+}
+
+impl From<Error> for FileSystemError {
+    fn from(error: Error) -> Self {
+        Self::Machine(error)
+    }
+}
+
+impl From<io::Error> for FileSystemError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
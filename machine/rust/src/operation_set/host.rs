@@ -0,0 +1,302 @@
+mod error;
+mod seeded;
+#[cfg(feature = "std")]
+mod system;
+
+pub use self::error::HostError;
+pub use self::seeded::SeededHost;
+#[cfg(feature = "std")]
+pub use self::system::SystemHost;
+use crate::{Error, Heap, Integer, Memory, OperationSet, Value};
+
+/// A host providing entropy and time.
+pub trait Host {
+    /// Generates a random number.
+    fn random(&mut self) -> u64;
+
+    /// Returns a monotonic time.
+    ///
+    /// A unit of time is up to a host.
+    fn time(&mut self) -> u64;
+}
+
+/// A host operation.
+///
+/// A code of an operation is its discriminant.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HostOperation {
+    /// Generates a random number.
+    Random,
+    /// Gets a time.
+    Time,
+}
+
+impl HostOperation {
+    const ALL: &[Self] = &[Self::Random, Self::Time];
+
+    /// A number of operations.
+    pub const COUNT: usize = Self::ALL.len();
+
+    /// Decodes an operation from a code.
+    pub fn from_code(code: usize) -> Option<Self> {
+        Self::ALL.get(code).copied()
+    }
+}
+
+/// A host operation set.
+///
+/// It pops arguments from and pushes results onto a list in a stack register.
+/// `Random` pops a positive bound and pushes a random number not less than
+/// `0` and less than the bound. `Time` pushes a time of a host. Times out of
+/// the range of numbers in values are overflow errors.
+#[derive(Debug)]
+pub struct HostOperationSet<T> {
+    host: T,
+    stack: usize,
+}
+
+impl<T: Host> HostOperationSet<T> {
+    /// Creates an operation set with a host and a stack register address.
+    pub const fn new(host: T, stack: usize) -> Self {
+        Self { host, stack }
+    }
+
+    /// Returns a host.
+    pub const fn host(&self) -> &T {
+        &self.host
+    }
+
+    fn random<V: Value, H: Heap<V>>(&mut self, memory: &mut Memory<V, H>) -> Result<(), HostError> {
+        let bound = memory
+            .get(memory.peek(self.stack, 0)?)?
+            .to_number()
+            .map_err(|_| Error::NumberExpected)?;
+
+        if bound <= Default::default() || V::Number::from_usize(bound.to_usize()) != bound {
+            return Err(HostError::InvalidBound);
+        }
+
+        memory.pop(self.stack)?;
+        let number = (u128::from(self.host.random()) * bound.to_usize() as u128) >> 64;
+
+        Ok(memory.push(self.stack, V::Number::from_usize(number as _).into())?)
+    }
+
+    fn time<V: Value, H: Heap<V>>(&mut self, memory: &mut Memory<V, H>) -> Result<(), HostError> {
+        let time = usize::try_from(self.host.time()).map_err(|_| HostError::Overflow)?;
+        let number = V::Number::from_usize(time);
+        let value = V::from(number);
+
+        if number.to_usize() != time || value.to_number() != Ok(number) {
+            return Err(HostError::Overflow);
+        }
+
+        Ok(memory.push(self.stack, value)?)
+    }
+}
+
+impl<V: Value, H: Heap<V>, T: Host> OperationSet<V, H> for HostOperationSet<T> {
+    type Error = HostError;
+
+    fn operate(&mut self, memory: &mut Memory<V, H>, code: usize) -> Result<(), Self::Error> {
+        match HostOperation::from_code(code).ok_or(HostError::InvalidOperation)? {
+            HostOperation::Random => self.random(memory),
+            HostOperation::Time => self.time(memory),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Machine, Value32, Value64, assemble};
+    use alloc::vec::Vec;
+    use pretty_assertions::assert_eq;
+
+    const HEAP_SIZE: usize = 1 << 8;
+    const STACK: usize = 0b11;
+
+    #[derive(Debug)]
+    struct FakeHost {
+        random: u64,
+        time: u64,
+    }
+
+    impl Host for FakeHost {
+        fn random(&mut self) -> u64 {
+            self.random
+        }
+
+        fn time(&mut self) -> u64 {
+            self.time
+        }
+    }
+
+    fn create_memory<V: Value>() -> Memory<V, [V; HEAP_SIZE]> {
+        let mut memory = Memory::new([Default::default(); _]).unwrap();
+        let root = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(root.into());
+
+        memory
+    }
+
+    fn operate<V: Value, T: Host>(
+        operation_set: &mut HostOperationSet<T>,
+        memory: &mut Memory<V, [V; HEAP_SIZE]>,
+        operation: HostOperation,
+        arguments: &[V],
+    ) -> Result<V, HostError> {
+        for &argument in arguments {
+            memory.push(STACK, argument).unwrap();
+        }
+
+        operation_set.operate(memory, operation as _)?;
+
+        Ok(memory.pop(STACK)?)
+    }
+
+    #[test]
+    fn generate_random_numbers_in_range() {
+        let mut memory = create_memory::<Value64>();
+
+        for (random, bound, number) in [
+            (0, 1, 0),
+            (u64::MAX, 1, 0),
+            (0, 10, 0),
+            (u64::MAX, 10, 9),
+            (1 << 63, 10, 5),
+            (u64::MAX, 1 << 60, (1 << 60) - 1),
+        ] {
+            let mut operation_set = HostOperationSet::new(FakeHost { random, time: 0 }, STACK);
+
+            assert_eq!(
+                operate(
+                    &mut operation_set,
+                    &mut memory,
+                    HostOperation::Random,
+                    &[bound.into()]
+                ),
+                Ok(number.into())
+            );
+        }
+    }
+
+    #[test]
+    fn fail_to_generate_random_number_with_invalid_bound() {
+        let mut memory = create_memory::<Value64>();
+        let mut operation_set = HostOperationSet::new(SeededHost::new(0), STACK);
+
+        for bound in [0, -1] {
+            assert_eq!(
+                operate(
+                    &mut operation_set,
+                    &mut memory,
+                    HostOperation::Random,
+                    &[bound.into()]
+                ),
+                Err(HostError::InvalidBound)
+            );
+            assert_eq!(memory.pop(STACK), Ok(bound.into()));
+        }
+    }
+
+    #[test]
+    fn get_time() {
+        let mut memory = create_memory::<Value64>();
+        let mut operation_set = HostOperationSet::new(SeededHost::new(0), STACK);
+
+        for time in 0..3 {
+            assert_eq!(
+                operate(&mut operation_set, &mut memory, HostOperation::Time, &[]),
+                Ok(time.into())
+            );
+        }
+    }
+
+    #[test]
+    fn fail_to_get_time_out_of_range() {
+        let mut memory = create_memory::<Value32>();
+        let mut operation_set = HostOperationSet::new(
+            FakeHost {
+                random: 0,
+                time: 1 << 31,
+            },
+            STACK,
+        );
+
+        assert_eq!(
+            operate(&mut operation_set, &mut memory, HostOperation::Time, &[]),
+            Err(HostError::Overflow)
+        );
+    }
+
+    #[test]
+    fn fail_to_operate() {
+        let mut memory = create_memory::<Value64>();
+        let mut operation_set = HostOperationSet::new(SeededHost::new(0), STACK);
+
+        assert_eq!(
+            operation_set.operate(&mut memory, HostOperation::Random as _),
+            Err(HostError::Machine(Error::ConsExpected))
+        );
+        assert_eq!(
+            operation_set.operate(&mut memory, HostOperation::COUNT),
+            Err(HostError::InvalidOperation)
+        );
+    }
+
+    #[test]
+    fn reproduce_heap_with_seed() {
+        let run = |seed| {
+            let mut memory = create_memory::<Value64>();
+            let mut operation_set = HostOperationSet::new(SeededHost::new(seed), STACK);
+
+            for _ in 0..8 {
+                memory.push(STACK, 100.into()).unwrap();
+                operation_set
+                    .operate(&mut memory, HostOperation::Random as _)
+                    .unwrap();
+                operation_set
+                    .operate(&mut memory, HostOperation::Time as _)
+                    .unwrap();
+            }
+
+            (0..memory.size())
+                .map(|index| memory.get(index).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
+    }
+
+    #[test]
+    fn run_program() {
+        let mut machine = Machine::<Value64, [Value64; HEAP_SIZE], 0b10, _>::new(
+            [Default::default(); _],
+            HostOperationSet::new(SeededHost::new(0), STACK),
+        )
+        .unwrap();
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(
+            "
+            cons 0b11 0
+            cons 0b11 1
+            operate 0 ; random
+            ",
+            &mut bytecode,
+        )
+        .unwrap();
+        let output = machine.evaluate(bytecode, STACK).unwrap();
+
+        assert_eq!(output.car().unwrap().to_number(), Ok(0));
+
+        let mut host = SeededHost::new(0);
+        host.random();
+
+        assert_eq!(machine.operation_set().host(), &host);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::Error;
+use core::{
+    error,
+    fmt::{self, Display, Formatter},
+};
+
+/// A host error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum HostError {
+    /// Invalid bound.
+    InvalidBound,
+    /// Invalid operation.
+    InvalidOperation,
+    /// A machine error.
+    Machine(Error),
+    /// Integer overflow.
+    Overflow,
+}
+
+impl error::Error for HostError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Machine(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for HostError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidBound => write!(formatter, "invalid bound"),
+            Self::InvalidOperation => write!(formatter, "invalid operation"),
+            Self::Machine(_) => write!(formatter, "machine error"),
+            Self::Overflow => write!(formatter, "integer overflow"),
+        }
+    }
+}
+
+impl From<Error> for HostError {
+    fn from(error: Error) -> Self {
+        Self::Machine(error)
+    }
+}
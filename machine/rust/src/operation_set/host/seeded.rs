@@ -0,0 +1,74 @@
+use super::Host;
+
+/// A deterministic host.
+///
+/// It generates random numbers with a SplitMix64 generator seeded by a given
+/// seed. Its clock is logical and advances by one on every query so that the
+/// same program with the same seed always behaves the same.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeededHost {
+    state: u64,
+    time: u64,
+}
+
+impl SeededHost {
+    /// Creates a host with a seed.
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            state: seed,
+            time: 0,
+        }
+    }
+}
+
+impl Host for SeededHost {
+    fn random(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut number = self.state;
+        number = (number ^ (number >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        number = (number ^ (number >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        number ^ (number >> 31)
+    }
+
+    fn time(&mut self) -> u64 {
+        let time = self.time;
+        self.time += 1;
+        time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn generate_random_numbers() {
+        let mut host = SeededHost::new(0);
+
+        assert_eq!(host.random(), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(host.random(), 0x6e78_9e6a_a1b9_65f4);
+    }
+
+    #[test]
+    fn repeat_random_numbers() {
+        let mut host = SeededHost::new(42);
+        let mut other = host.clone();
+
+        for _ in 0..8 {
+            assert_eq!(host.random(), other.random());
+        }
+
+        assert_ne!(SeededHost::new(0).random(), SeededHost::new(1).random());
+    }
+
+    #[test]
+    fn advance_time() {
+        let mut host = SeededHost::new(0);
+
+        assert_eq!(host.time(), 0);
+        assert_eq!(host.time(), 1);
+        assert_eq!(host.time(), 2);
+    }
+}
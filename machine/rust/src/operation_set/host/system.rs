@@ -0,0 +1,56 @@
+use super::{Host, SeededHost};
+use core::hash::{BuildHasher, Hasher};
+use std::{collections::hash_map::RandomState, time::Instant};
+
+/// A host of a system.
+///
+/// It generates random numbers with a generator seeded by entropy of a system
+/// and measures time in milliseconds since its creation with a monotonic
+/// clock.
+#[derive(Debug)]
+pub struct SystemHost {
+    random: SeededHost,
+    start: Instant,
+}
+
+impl SystemHost {
+    /// Creates a host.
+    pub fn new() -> Self {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u8(0);
+
+        Self {
+            random: SeededHost::new(hasher.finish()),
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemHost {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Host for SystemHost {
+    fn random(&mut self) -> u64 {
+        self.random.random()
+    }
+
+    fn time(&mut self) -> u64 {
+        self.start.elapsed().as_millis() as _
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance_time() {
+        let mut host = SystemHost::new();
+        let time = host.time();
+
+        assert!(host.time() >= time);
+    }
+}
@@ -0,0 +1,319 @@
+mod error;
+
+pub use self::error::IoError;
+use crate::{Error, Heap, Integer, Memory, OperationSet, Value};
+use std::io::{self, ErrorKind, Read, Write};
+
+/// An I/O operation.
+///
+/// A code of an operation is its discriminant.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IoOperation {
+    /// Reads a byte.
+    ReadByte,
+    /// Writes a byte.
+    WriteByte,
+    /// Peeks a byte without reading it.
+    Peek,
+    /// Flushes a writer.
+    Flush,
+}
+
+impl IoOperation {
+    const ALL: &[Self] = &[Self::ReadByte, Self::WriteByte, Self::Peek, Self::Flush];
+
+    /// A number of operations.
+    pub const COUNT: usize = Self::ALL.len();
+
+    /// Decodes an operation from a code.
+    pub fn from_code(code: usize) -> Option<Self> {
+        Self::ALL.get(code).copied()
+    }
+}
+
+/// An I/O operation set.
+///
+/// It pops arguments from and pushes results onto a list in a stack register.
+/// `ReadByte` and `Peek` push a byte or `-1` at the end of input. `WriteByte`
+/// pops a byte and pushes nothing.
+#[derive(Debug)]
+pub struct IoOperationSet<R, W> {
+    reader: R,
+    writer: W,
+    stack: usize,
+    peeked: Option<Option<u8>>,
+}
+
+impl<R: Read, W: Write> IoOperationSet<R, W> {
+    /// Creates an operation set with a reader, a writer, and a stack register
+    /// address.
+    pub const fn new(reader: R, writer: W, stack: usize) -> Self {
+        Self {
+            reader,
+            writer,
+            stack,
+            peeked: None,
+        }
+    }
+
+    /// Returns a reader.
+    pub const fn reader(&self) -> &R {
+        &self.reader
+    }
+
+    /// Returns a writer.
+    pub const fn writer(&self) -> &W {
+        &self.writer
+    }
+
+    fn peek(&mut self) -> Result<Option<u8>, IoError> {
+        if let Some(byte) = self.peeked {
+            return Ok(byte);
+        }
+
+        let byte = read_byte(&mut self.reader)?;
+        self.peeked = Some(byte);
+
+        Ok(byte)
+    }
+}
+
+impl<V: Value, H: Heap<V>, R: Read, W: Write> OperationSet<V, H> for IoOperationSet<R, W> {
+    type Error = IoError;
+
+    fn operate(&mut self, memory: &mut Memory<V, H>, code: usize) -> Result<(), Self::Error> {
+        match IoOperation::from_code(code).ok_or(IoError::InvalidOperation)? {
+            IoOperation::ReadByte => {
+                let byte = self.peek()?;
+                push_byte(memory, self.stack, byte)?;
+                self.peeked = None;
+            }
+            IoOperation::WriteByte => {
+                let byte = peek_byte(memory, self.stack, 0)?.ok_or(IoError::InvalidByte)?;
+
+                self.writer.write_all(&[byte])?;
+                memory.pop(self.stack)?;
+            }
+            IoOperation::Peek => {
+                let byte = self.peek()?;
+                push_byte(memory, self.stack, byte)?;
+            }
+            IoOperation::Flush => self.writer.flush()?,
+        }
+
+        Ok(())
+    }
+}
+
+// Reads a byte retrying on interrupts.
+pub(super) fn read_byte(reader: &mut impl Read) -> io::Result<Option<u8>> {
+    let mut buffer = [0];
+
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(buffer[0])),
+            Err(error) if error.kind() == ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+// Pushes a byte or `-1` at the end of input.
+pub(super) fn push_byte<V: Value, H: Heap<V>>(
+    memory: &mut Memory<V, H>,
+    stack: usize,
+    byte: Option<u8>,
+) -> Result<(), Error> {
+    memory.push(
+        stack,
+        byte.map_or_else(|| V::Number::from_i64(-1), V::Number::from)
+            .into(),
+    )
+}
+
+// Peeks a byte at a position in a stack without popping it. It returns `None`
+// if a number is out of the range of bytes.
+pub(super) fn peek_byte<V: Value, H: Heap<V>>(
+    memory: &Memory<V, H>,
+    stack: usize,
+    position: usize,
+) -> Result<Option<u8>, Error> {
+    let byte = memory
+        .get(memory.peek(stack, position)?)?
+        .to_number()
+        .map_err(|_| Error::NumberExpected)?;
+
+    Ok((byte >= Default::default() && byte <= u8::MAX.into()).then(|| byte.to_usize() as _))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Machine, Value64, assemble};
+    use alloc::vec::Vec;
+    use pretty_assertions::assert_eq;
+    use std::io;
+
+    const HEAP_SIZE: usize = 1 << 8;
+    const STACK: usize = 0b11;
+
+    type TestMemory = Memory<Value64, [Value64; HEAP_SIZE]>;
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buffer: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Err(io::ErrorKind::BrokenPipe.into())
+        }
+    }
+
+    fn create_memory() -> TestMemory {
+        let mut memory = Memory::new([Default::default(); _]).unwrap();
+        let root = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(root.into());
+
+        memory
+    }
+
+    fn operate<R: Read, W: Write>(
+        operation_set: &mut IoOperationSet<R, W>,
+        memory: &mut TestMemory,
+        operation: IoOperation,
+    ) -> Result<(), IoError> {
+        operation_set.operate(memory, operation as _)
+    }
+
+    #[test]
+    fn read_bytes() {
+        let mut memory = create_memory();
+        let mut operation_set = IoOperationSet::new(&b"ab"[..], Vec::new(), STACK);
+
+        for byte in [b'a' as i64, b'b' as _, -1] {
+            operate(&mut operation_set, &mut memory, IoOperation::ReadByte).unwrap();
+
+            assert_eq!(memory.pop(STACK), Ok(byte.into()));
+        }
+    }
+
+    #[test]
+    fn keep_byte_on_failed_read() {
+        let mut memory = create_memory();
+        let mut operation_set = IoOperationSet::new(&b"ab"[..], Vec::new(), STACK);
+
+        while memory.push(STACK, 0.into()).is_ok() {}
+
+        assert!(matches!(
+            operate(&mut operation_set, &mut memory, IoOperation::ReadByte),
+            Err(IoError::Machine(Error::OutOfMemory))
+        ));
+
+        memory.pop(STACK).unwrap();
+        operate(&mut operation_set, &mut memory, IoOperation::ReadByte).unwrap();
+
+        assert_eq!(memory.pop(STACK), Ok((b'a' as i64).into()));
+    }
+
+    #[test]
+    fn peek_byte() {
+        let mut memory = create_memory();
+        let mut operation_set = IoOperationSet::new(&b"a"[..], Vec::new(), STACK);
+
+        for operation in [IoOperation::Peek, IoOperation::Peek, IoOperation::ReadByte] {
+            operate(&mut operation_set, &mut memory, operation).unwrap();
+
+            assert_eq!(memory.pop(STACK), Ok((b'a' as i64).into()));
+        }
+
+        operate(&mut operation_set, &mut memory, IoOperation::Peek).unwrap();
+
+        assert_eq!(memory.pop(STACK), Ok((-1).into()));
+    }
+
+    #[test]
+    fn write_bytes() {
+        let mut memory = create_memory();
+        let mut operation_set = IoOperationSet::new(&[][..], Vec::new(), STACK);
+
+        for byte in *b"ab" {
+            memory.push(STACK, (byte as i64).into()).unwrap();
+            operate(&mut operation_set, &mut memory, IoOperation::WriteByte).unwrap();
+        }
+
+        operate(&mut operation_set, &mut memory, IoOperation::Flush).unwrap();
+
+        assert_eq!(operation_set.writer(), b"ab");
+        assert_eq!(memory.pop(STACK), Err(Error::ConsExpected));
+    }
+
+    #[test]
+    fn fail_to_write_invalid_byte() {
+        for byte in [-1, 256] {
+            let mut memory = create_memory();
+            let mut operation_set = IoOperationSet::new(&[][..], Vec::new(), STACK);
+            memory.push(STACK, byte.into()).unwrap();
+
+            assert!(matches!(
+                operate(&mut operation_set, &mut memory, IoOperation::WriteByte),
+                Err(IoError::InvalidByte)
+            ));
+            assert_eq!(memory.pop(STACK), Ok(byte.into()));
+        }
+    }
+
+    #[test]
+    fn fail_to_write_byte() {
+        let mut memory = create_memory();
+        let mut operation_set = IoOperationSet::new(&[][..], FailingWriter, STACK);
+        memory.push(STACK, 0.into()).unwrap();
+
+        assert!(matches!(
+            operate(&mut operation_set, &mut memory, IoOperation::WriteByte),
+            Err(IoError::Io(error)) if error.kind() == io::ErrorKind::BrokenPipe
+        ));
+        assert_eq!(memory.pop(STACK), Ok(0.into()));
+        assert!(matches!(
+            operate(&mut operation_set, &mut memory, IoOperation::Flush),
+            Err(IoError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn fail_to_run_invalid_operation() {
+        assert!(matches!(
+            IoOperationSet::new(&[][..], Vec::new(), STACK)
+                .operate(&mut create_memory(), IoOperation::COUNT),
+            Err(IoError::InvalidOperation)
+        ));
+    }
+
+    #[test]
+    fn run_program() {
+        let mut machine = Machine::<Value64, [Value64; HEAP_SIZE], 0b10, _>::new(
+            [Default::default(); _],
+            IoOperationSet::new(&b"x"[..], Vec::new(), STACK),
+        )
+        .unwrap();
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(
+            "
+            operate 0 ; read
+            operate 1 ; write
+            cons 0b11 10
+            operate 1 ; write
+            ",
+            &mut bytecode,
+        )
+        .unwrap();
+        machine.run(bytecode).unwrap();
+
+        assert_eq!(machine.operation_set().writer(), b"x\n");
+    }
+}
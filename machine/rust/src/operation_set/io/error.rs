@@ -0,0 +1,52 @@
+use crate::Error;
+use core::{
+    error,
+    fmt::{self, Display, Formatter},
+};
+use std::io;
+
+/// An I/O error.
+#[derive(Debug)]
+pub enum IoError {
+    /// Invalid byte.
+    InvalidByte,
+    /// Invalid operation.
+    InvalidOperation,
+    /// An I/O error of a reader or a writer.
+    Io(io::Error),
+    /// A machine error.
+    Machine(Error),
+}
+
+impl error::Error for IoError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Machine(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for IoError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidByte => write!(formatter, "invalid byte"),
+            Self::InvalidOperation => write!(formatter, "invalid operation"),
+            Self::Io(_) => write!(formatter, "I/O error"),
+            Self::Machine(_) => write!(formatter, "machine error"),
+        }
+    }
+}
+
+impl From<Error> for IoError {
+    fn from(error: Error) -> Self {
+        Self::Machine(error)
+    }
+}
+
+impl From<io::Error> for IoError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
@@ -0,0 +1,385 @@
+mod error;
+
+pub use self::error::ListError;
+use crate::{Cons, Error, Heap, Integer, Memory, OperationSet, Value, cons::Tag};
+
+/// A list operation.
+///
+/// A code of an operation is its discriminant.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ListOperation {
+    /// Gets a car of a cons.
+    Car,
+    /// Gets a cdr of a cons.
+    Cdr,
+    /// Sets a car of a cons.
+    SetCar,
+    /// Sets a cdr of a cons.
+    SetCdr,
+    /// Checks if a value is a cons.
+    Pair,
+    /// Checks if a value is null.
+    Null,
+    /// Checks if values are identical.
+    Eq,
+    /// Gets a tag of a cons.
+    Tag,
+    /// Sets a tag of a cons.
+    SetTag,
+}
+
+impl ListOperation {
+    const ALL: &[Self] = &[
+        Self::Car,
+        Self::Cdr,
+        Self::SetCar,
+        Self::SetCdr,
+        Self::Pair,
+        Self::Null,
+        Self::Eq,
+        Self::Tag,
+        Self::SetTag,
+    ];
+
+    /// A number of operations.
+    pub const COUNT: usize = Self::ALL.len();
+
+    /// Decodes an operation from a code.
+    pub fn from_code(code: usize) -> Option<Self> {
+        Self::ALL.get(code).copied()
+    }
+}
+
+/// A list operation set.
+///
+/// It pops arguments from and pushes results onto a list in a stack register.
+/// The last argument is on the top of a stack. `SetCar` and `SetCdr` pop a
+/// cons and a value and push nothing. `SetTag` pops a cons and a tag and
+/// pushes the cons with the tag. Predicates push `1` if they hold and `0`
+/// otherwise. Null is the number `0`.
+///
+/// Operations replace arguments on a stack in place and never allocate memory.
+/// Arguments are validated before they are popped so that a failed operation
+/// leaves a stack unchanged.
+#[derive(Debug)]
+pub struct ListOperationSet {
+    stack: usize,
+}
+
+impl ListOperationSet {
+    /// Creates an operation set with a stack register address.
+    pub const fn new(stack: usize) -> Self {
+        Self { stack }
+    }
+
+    fn cons<V: Value>(value: V) -> Result<Cons<V>, ListError> {
+        value.to_cons().map_err(|_| ListError::ConsExpected)
+    }
+
+    fn boolean<V: Value>(value: bool) -> V {
+        V::Number::from(u8::from(value)).into()
+    }
+}
+
+impl<V: Value, H: Heap<V>> OperationSet<V, H> for ListOperationSet {
+    type Error = ListError;
+
+    fn operate(&mut self, memory: &mut Memory<V, H>, code: usize) -> Result<(), Self::Error> {
+        let operation = ListOperation::from_code(code).ok_or(ListError::InvalidOperation)?;
+
+        match operation {
+            ListOperation::Car | ListOperation::Cdr => {
+                let index = memory.peek(self.stack, 0)?;
+                let cons = Self::cons(memory.get(index)?)?;
+
+                memory.set(
+                    index,
+                    memory.get(cons.index() + usize::from(operation == ListOperation::Cdr))?,
+                )?;
+            }
+            ListOperation::SetCar | ListOperation::SetCdr => {
+                let value = memory.get(memory.peek(self.stack, 0)?)?;
+                let cons = Self::cons(memory.get(memory.peek(self.stack, 1)?)?)?;
+
+                memory.pop(self.stack)?;
+                memory.pop(self.stack)?;
+                memory.set(
+                    cons.index() + usize::from(operation == ListOperation::SetCdr),
+                    value,
+                )?;
+            }
+            ListOperation::Pair | ListOperation::Null => {
+                let index = memory.peek(self.stack, 0)?;
+                let value = memory.get(index)?;
+
+                memory.set(
+                    index,
+                    Self::boolean(if operation == ListOperation::Pair {
+                        value.is_pointer()
+                    } else {
+                        value == Default::default()
+                    }),
+                )?;
+            }
+            ListOperation::Eq => {
+                let value = memory.pop(self.stack)?;
+                let index = memory.peek(self.stack, 0)?;
+
+                memory.set(index, Self::boolean(memory.get(index)? == value))?;
+            }
+            ListOperation::Tag => {
+                let index = memory.peek(self.stack, 0)?;
+                let tag = Self::cons(memory.get(index)?)?.tag();
+
+                memory.set(index, V::Number::from(tag).into())?;
+            }
+            ListOperation::SetTag => {
+                let tag = memory
+                    .get(memory.peek(self.stack, 0)?)?
+                    .to_number()
+                    .map_err(|_| Error::NumberExpected)?;
+                let index = memory.peek(self.stack, 1)?;
+                let cons = Self::cons(memory.get(index)?)?;
+
+                if tag < Default::default() || tag > Tag::MAX.into() {
+                    return Err(ListError::InvalidTag);
+                }
+
+                memory.pop(self.stack)?;
+                memory.set(index, cons.set_tag(tag.to_usize() as _).into())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Machine, Value64, assemble};
+    use alloc::vec::Vec;
+    use pretty_assertions::assert_eq;
+
+    const HEAP_SIZE: usize = 1 << 8;
+    const STACK: usize = 0b11;
+
+    type TestMemory = Memory<Value64, [Value64; HEAP_SIZE]>;
+
+    fn create_memory() -> TestMemory {
+        let mut memory = Memory::new([Default::default(); _]).unwrap();
+        let root = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(root.into());
+
+        memory
+    }
+
+    fn push_cons(memory: &mut TestMemory, car: i64, cdr: i64) -> Cons<Value64> {
+        memory.push(STACK, Default::default()).unwrap();
+        let cons = memory.allocate(car.into(), cdr.into()).unwrap();
+        let index = memory.resolve(STACK).unwrap();
+        let top = Cons::from(memory.get(index).unwrap());
+        memory.set(top.index(), cons.into()).unwrap();
+
+        cons
+    }
+
+    fn operate(memory: &mut TestMemory, operation: ListOperation) -> Result<(), ListError> {
+        ListOperationSet::new(STACK).operate(memory, operation as _)
+    }
+
+    #[test]
+    fn get_car_and_cdr() {
+        for (operation, value) in [(ListOperation::Car, 1), (ListOperation::Cdr, 2)] {
+            let mut memory = create_memory();
+            push_cons(&mut memory, 1, 2);
+
+            operate(&mut memory, operation).unwrap();
+
+            assert_eq!(memory.pop(STACK), Ok(value.into()));
+            assert_eq!(memory.pop(STACK), Err(Error::ConsExpected));
+        }
+    }
+
+    #[test]
+    fn set_car_and_cdr() {
+        for (operation, car, cdr) in [(ListOperation::SetCar, 3, 2), (ListOperation::SetCdr, 1, 3)]
+        {
+            let mut memory = create_memory();
+            let cons = push_cons(&mut memory, 1, 2);
+            memory.push(STACK, 3.into()).unwrap();
+
+            operate(&mut memory, operation).unwrap();
+
+            assert_eq!(memory.get(cons.index()), Ok(car.into()));
+            assert_eq!(memory.get(cons.index() + 1), Ok(cdr.into()));
+            assert_eq!(memory.pop(STACK), Err(Error::ConsExpected));
+        }
+    }
+
+    #[test]
+    fn check_pair() {
+        let mut memory = create_memory();
+        push_cons(&mut memory, 1, 2);
+        memory.push(STACK, 1.into()).unwrap();
+
+        operate(&mut memory, ListOperation::Pair).unwrap();
+        assert_eq!(memory.pop(STACK), Ok(0.into()));
+        operate(&mut memory, ListOperation::Pair).unwrap();
+        assert_eq!(memory.pop(STACK), Ok(1.into()));
+    }
+
+    #[test]
+    fn check_null() {
+        let mut memory = create_memory();
+        memory.push(STACK, 1.into()).unwrap();
+        memory.push(STACK, 0.into()).unwrap();
+
+        operate(&mut memory, ListOperation::Null).unwrap();
+        assert_eq!(memory.pop(STACK), Ok(1.into()));
+        operate(&mut memory, ListOperation::Null).unwrap();
+        assert_eq!(memory.pop(STACK), Ok(0.into()));
+    }
+
+    #[test]
+    fn check_identity() {
+        let mut memory = create_memory();
+        let cons = push_cons(&mut memory, 1, 2);
+        memory.push(STACK, cons.into()).unwrap();
+
+        operate(&mut memory, ListOperation::Eq).unwrap();
+
+        assert_eq!(memory.pop(STACK), Ok(1.into()));
+    }
+
+    #[test]
+    fn check_non_identity() {
+        let mut memory = create_memory();
+        push_cons(&mut memory, 1, 2);
+        push_cons(&mut memory, 1, 2);
+
+        operate(&mut memory, ListOperation::Eq).unwrap();
+
+        assert_eq!(memory.pop(STACK), Ok(0.into()));
+    }
+
+    #[test]
+    fn get_and_set_tag() {
+        let mut memory = create_memory();
+        let cons = push_cons(&mut memory, 1, 2);
+        memory.push(STACK, 42.into()).unwrap();
+
+        operate(&mut memory, ListOperation::SetTag).unwrap();
+
+        assert_eq!(
+            memory.get(Cons::from(memory.get(memory.resolve(STACK).unwrap()).unwrap()).index()),
+            Ok(cons.set_tag(42).into())
+        );
+
+        operate(&mut memory, ListOperation::Tag).unwrap();
+
+        assert_eq!(memory.pop(STACK), Ok(42.into()));
+    }
+
+    #[test]
+    fn fail_to_operate_on_number() {
+        for operation in [
+            ListOperation::Car,
+            ListOperation::Cdr,
+            ListOperation::SetCar,
+            ListOperation::SetCdr,
+            ListOperation::Tag,
+            ListOperation::SetTag,
+        ] {
+            let mut memory = create_memory();
+            memory.push(STACK, 1.into()).unwrap();
+            memory.push(STACK, 2.into()).unwrap();
+
+            assert_eq!(
+                operate(&mut memory, operation),
+                Err(ListError::ConsExpected),
+                "{operation:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn fail_to_set_invalid_tag() {
+        for tag in [-1, 256] {
+            let mut memory = create_memory();
+            push_cons(&mut memory, 1, 2);
+            memory.push(STACK, tag.into()).unwrap();
+
+            assert_eq!(
+                operate(&mut memory, ListOperation::SetTag),
+                Err(ListError::InvalidTag)
+            );
+        }
+    }
+
+    #[test]
+    fn keep_arguments_on_failure() {
+        for (operation, argument, error) in [
+            (ListOperation::SetCar, 1, ListError::ConsExpected),
+            (ListOperation::SetCdr, 1, ListError::ConsExpected),
+            (ListOperation::SetTag, 256, ListError::InvalidTag),
+        ] {
+            let mut memory = create_memory();
+            let value = if operation == ListOperation::SetTag {
+                push_cons(&mut memory, 1, 2).into()
+            } else {
+                memory.push(STACK, 2.into()).unwrap();
+                2.into()
+            };
+            memory.push(STACK, argument.into()).unwrap();
+
+            assert_eq!(operate(&mut memory, operation), Err(error), "{operation:?}");
+            assert_eq!(memory.pop(STACK), Ok(argument.into()));
+            assert_eq!(memory.pop(STACK), Ok(value));
+            assert_eq!(memory.pop(STACK), Err(Error::ConsExpected));
+        }
+    }
+
+    #[test]
+    fn fail_to_operate_on_empty_stack() {
+        assert_eq!(
+            operate(&mut create_memory(), ListOperation::Car),
+            Err(ListError::Machine(Error::ConsExpected))
+        );
+    }
+
+    #[test]
+    fn fail_to_run_invalid_operation() {
+        assert_eq!(
+            ListOperationSet::new(STACK).operate(&mut create_memory(), ListOperation::ALL.len()),
+            Err(ListError::InvalidOperation)
+        );
+    }
+
+    #[test]
+    fn run_program() {
+        let mut machine = Machine::<Value64, [Value64; HEAP_SIZE], 0b10, _>::new(
+            [Default::default(); _],
+            ListOperationSet::new(STACK),
+        )
+        .unwrap();
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(
+            "
+            cons 0b11 0
+            operate 5 ; null?
+            cons 0b11 7
+            operate 4 ; pair?
+            ",
+            &mut bytecode,
+        )
+        .unwrap();
+        let output = machine.evaluate(bytecode, STACK).unwrap();
+
+        assert_eq!(output.car().unwrap().to_number(), Ok(0));
+        assert_eq!(output.cdr().unwrap().car().unwrap().to_number(), Ok(1));
+    }
+}
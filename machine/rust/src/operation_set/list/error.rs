@@ -0,0 +1,44 @@
+use crate::Error;
+use core::{
+    error,
+    fmt::{self, Display, Formatter},
+};
+
+/// A list error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ListError {
+    /// Cons expected.
+    ConsExpected,
+    /// Invalid operation.
+    InvalidOperation,
+    /// Invalid tag.
+    InvalidTag,
+    /// A machine error.
+    Machine(Error),
+}
+
+impl error::Error for ListError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Machine(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ListError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::ConsExpected => write!(formatter, "cons expected"),
+            Self::InvalidOperation => write!(formatter, "invalid operation"),
+            Self::InvalidTag => write!(formatter, "invalid tag"),
+            Self::Machine(_) => write!(formatter, "machine error"),
+        }
+    }
+}
+
+impl From<Error> for ListError {
+    fn from(error: Error) -> Self {
+        Self::Machine(error)
+    }
+}
@@ -0,0 +1,214 @@
+mod error;
+
+pub use self::error::RegistryError;
+use crate::{Memory, OperationSet};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use core::fmt::{self, Debug, Formatter};
+
+type Procedure<'a, V, H, E> = Box<dyn FnMut(&mut Memory<V, H>) -> Result<(), E> + 'a>;
+
+/// An operation set of procedures registered by a host.
+///
+/// Each procedure is registered under a name and assigned a code in the
+/// order of registration. Programs call procedures by the codes with the
+/// `operate` instruction while assemblers or compilers resolve symbolic names
+/// into the codes with [`code`](Self::code).
+pub struct RegistryOperationSet<'a, V, H, E> {
+    procedures: Vec<(String, Procedure<'a, V, H, E>)>,
+}
+
+impl<'a, V, H, E> RegistryOperationSet<'a, V, H, E> {
+    /// Creates an operation set.
+    pub const fn new() -> Self {
+        Self {
+            procedures: Vec::new(),
+        }
+    }
+
+    /// Registers a procedure under a name and returns its code.
+    ///
+    /// If a procedure is already registered under the name, it is replaced
+    /// keeping its code.
+    pub fn register(
+        &mut self,
+        name: &str,
+        procedure: impl FnMut(&mut Memory<V, H>) -> Result<(), E> + 'a,
+    ) -> usize {
+        let procedure = Box::new(procedure);
+
+        if let Some(code) = self.code(name) {
+            self.procedures[code].1 = procedure;
+            code
+        } else {
+            self.procedures.push((name.into(), procedure));
+            self.procedures.len() - 1
+        }
+    }
+
+    /// Returns a code of a procedure.
+    pub fn code(&self, name: &str) -> Option<usize> {
+        self.procedures.iter().position(|(other, _)| other == name)
+    }
+
+    /// Returns a name of a procedure.
+    pub fn name(&self, code: usize) -> Option<&str> {
+        self.procedures.get(code).map(|(name, _)| name.as_str())
+    }
+
+    /// Returns names of procedures in the order of their codes.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.procedures.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns a number of procedures.
+    pub fn len(&self) -> usize {
+        self.procedures.len()
+    }
+
+    /// Returns `true` if no procedure is registered.
+    pub fn is_empty(&self) -> bool {
+        self.procedures.is_empty()
+    }
+}
+
+impl<V, H, E> Default for RegistryOperationSet<'_, V, H, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, H, E> Debug for RegistryOperationSet<'_, V, H, E> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        formatter.debug_list().entries(self.names()).finish()
+    }
+}
+
+impl<V, H, E> OperationSet<V, H> for RegistryOperationSet<'_, V, H, E> {
+    type Error = RegistryError<E>;
+
+    fn operate(&mut self, memory: &mut Memory<V, H>, code: usize) -> Result<(), Self::Error> {
+        let (_, procedure) = self
+            .procedures
+            .get_mut(code)
+            .ok_or(RegistryError::InvalidOperation)?;
+
+        procedure(memory).map_err(RegistryError::Procedure)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, Machine, Value64, assemble};
+    use alloc::format;
+    use pretty_assertions::assert_eq;
+
+    const HEAP_SIZE: usize = 1 << 8;
+    const STACK: usize = 0b11;
+
+    type TestMemory = Memory<Value64, [Value64; HEAP_SIZE]>;
+
+    fn create_memory() -> TestMemory {
+        let mut memory = Memory::new([Default::default(); _]).unwrap();
+        let root = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(root.into());
+
+        memory
+    }
+
+    #[test]
+    fn map_names_to_codes() {
+        let mut operation_set = RegistryOperationSet::<Value64, [Value64; HEAP_SIZE], Error>::new();
+
+        assert!(operation_set.is_empty());
+        assert_eq!(operation_set.register("foo", |_| Ok(())), 0);
+        assert_eq!(operation_set.register("bar", |_| Ok(())), 1);
+        assert_eq!(operation_set.register("foo", |_| Ok(())), 0);
+
+        assert_eq!(operation_set.len(), 2);
+        assert_eq!(operation_set.code("bar"), Some(1));
+        assert_eq!(operation_set.code("baz"), None);
+        assert_eq!(operation_set.name(0), Some("foo"));
+        assert_eq!(operation_set.name(2), None);
+        assert_eq!(operation_set.names().collect::<Vec<_>>(), ["foo", "bar"]);
+    }
+
+    #[test]
+    fn call_procedures() {
+        let mut memory = create_memory();
+        let mut count = 0;
+        let mut operation_set = RegistryOperationSet::new();
+
+        let increment = operation_set.register("increment", |_: &mut TestMemory| {
+            count += 1;
+            Ok::<_, Error>(())
+        });
+        let push = operation_set.register("push", |memory| memory.push(STACK, 42.into()));
+
+        operation_set.operate(&mut memory, increment).unwrap();
+        operation_set.operate(&mut memory, increment).unwrap();
+        operation_set.operate(&mut memory, push).unwrap();
+
+        assert_eq!(memory.pop(STACK), Ok(42.into()));
+        drop(operation_set);
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn replace_procedure() {
+        let mut memory = create_memory();
+        let mut operation_set = RegistryOperationSet::new();
+
+        let code = operation_set.register("foo", |memory: &mut TestMemory| {
+            memory.push(STACK, 1.into())
+        });
+        operation_set.register("foo", |memory| memory.push(STACK, 2.into()));
+        operation_set.operate(&mut memory, code).unwrap();
+
+        assert_eq!(memory.pop(STACK), Ok(2.into()));
+    }
+
+    #[test]
+    fn fail_to_operate() {
+        let mut memory = create_memory();
+        let mut operation_set = RegistryOperationSet::new();
+
+        let code =
+            operation_set.register("pop", |memory: &mut TestMemory| memory.pop(STACK).map(drop));
+
+        assert_eq!(
+            operation_set.operate(&mut memory, code),
+            Err(RegistryError::Procedure(Error::ConsExpected))
+        );
+        assert_eq!(
+            operation_set.operate(&mut memory, code + 1),
+            Err(RegistryError::InvalidOperation)
+        );
+    }
+
+    #[test]
+    fn run_program() {
+        let mut operation_set = RegistryOperationSet::new();
+        operation_set.register("answer", |memory: &mut TestMemory| {
+            memory.push(STACK, 42.into())
+        });
+        let code = operation_set.code("answer").unwrap();
+        let mut machine = Machine::<Value64, [Value64; HEAP_SIZE], 0b10, _>::new(
+            [Default::default(); _],
+            operation_set,
+        )
+        .unwrap();
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(
+            &format!("cons 0b11 0\noperate {code} ; answer"),
+            &mut bytecode,
+        )
+        .unwrap();
+        let output = machine.evaluate(bytecode, STACK).unwrap();
+
+        assert_eq!(output.car().unwrap().to_number(), Ok(42));
+    }
+}
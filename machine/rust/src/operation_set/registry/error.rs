@@ -0,0 +1,31 @@
+use core::{
+    error,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+/// An error of a registry operation set.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RegistryError<E> {
+    /// An invalid operation.
+    InvalidOperation,
+    /// An error of a procedure.
+    Procedure(E),
+}
+
+impl<E: error::Error + 'static> error::Error for RegistryError<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::InvalidOperation => None,
+            Self::Procedure(error) => Some(error),
+        }
+    }
+}
+
+impl<E> Display for RegistryError<E> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidOperation => write!(formatter, "invalid operation"),
+            Self::Procedure(_) => write!(formatter, "procedure error"),
+        }
+    }
+}
@@ -1,3 +1,6 @@
+mod error;
+
+pub use self::error::VoidError;
 use crate::{Memory, OperationSet};
 
 /// A void operation set.
@@ -12,10 +15,10 @@ impl VoidOperationSet {
 }
 
 impl<V, H> OperationSet<V, H> for VoidOperationSet {
-    type Error = &'static str;
+    type Error = VoidError;
 
     fn operate(&mut self, _memory: &mut Memory<V, H>, _code: usize) -> Result<(), Self::Error> {
-        Err("invalid operation")
+        Err(VoidError::InvalidOperation)
     }
 }
 
@@ -23,6 +26,7 @@ impl<V, H> OperationSet<V, H> for VoidOperationSet {
 mod tests {
     use super::*;
     use crate::Value64;
+    use pretty_assertions::assert_eq;
 
     const HEAP_SIZE: usize = 1 << 8;
 
@@ -31,6 +35,9 @@ mod tests {
         let mut memory =
             Memory::<Value64, [Value64; HEAP_SIZE]>::new([Default::default(); _]).unwrap();
 
-        assert!(VoidOperationSet::new().operate(&mut memory, 0).is_err());
+        assert_eq!(
+            VoidOperationSet::new().operate(&mut memory, 0),
+            Err(VoidError::InvalidOperation)
+        );
     }
 }
@@ -0,0 +1,21 @@
+use core::{
+    error,
+    fmt::{self, Display, Formatter},
+};
+
+/// A void error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VoidError {
+    /// Invalid operation.
+    InvalidOperation,
+}
+
+impl error::Error for VoidError {}
+
+impl Display for VoidError {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidOperation => write!(formatter, "invalid operation"),
+        }
+    }
+}
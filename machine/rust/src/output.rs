@@ -0,0 +1,85 @@
+use crate::{Cons, Error, Heap, Memory, Value};
+
+/// An output value of a program.
+///
+/// It refers to a value in a memory of a machine so that a host can inspect
+/// a list structure of the value.
+#[derive(Debug)]
+pub struct Output<'a, V, H> {
+    memory: &'a Memory<V, H>,
+    value: V,
+}
+
+impl<'a, V: Value, H: Heap<V>> Output<'a, V, H> {
+    pub(crate) const fn new(memory: &'a Memory<V, H>, value: V) -> Self {
+        Self { memory, value }
+    }
+
+    /// Returns a raw value.
+    pub const fn value(&self) -> V {
+        self.value
+    }
+
+    /// Converts a value into a number.
+    pub fn to_number(&self) -> Result<V::Number, Error> {
+        self.value.to_number().map_err(|_| Error::NumberExpected)
+    }
+
+    /// Converts a value into a cons.
+    pub fn to_cons(&self) -> Result<Cons<V>, Error> {
+        self.value.to_cons().map_err(|_| Error::ConsExpected)
+    }
+
+    /// Returns a car of a cons.
+    pub fn car(&self) -> Result<Self, Error> {
+        self.field(0)
+    }
+
+    /// Returns a cdr of a cons.
+    pub fn cdr(&self) -> Result<Self, Error> {
+        self.field(1)
+    }
+
+    fn field(&self, offset: usize) -> Result<Self, Error> {
+        Ok(Self::new(
+            self.memory,
+            self.memory.get(self.to_cons()?.index() + offset)?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value64;
+    use pretty_assertions::assert_eq;
+
+    const HEAP_SIZE: usize = 1 << 8;
+
+    fn create_memory() -> Memory<Value64, [Value64; HEAP_SIZE]> {
+        Memory::new([Default::default(); _]).unwrap()
+    }
+
+    #[test]
+    fn convert_number() {
+        let memory = create_memory();
+        let output = Output::new(&memory, 42.into());
+
+        assert_eq!(output.value(), 42.into());
+        assert_eq!(output.to_number(), Ok(42));
+        assert_eq!(output.to_cons(), Err(Error::ConsExpected));
+        assert_eq!(output.car().err(), Some(Error::ConsExpected));
+    }
+
+    #[test]
+    fn inspect_cons() {
+        let mut memory = create_memory();
+        let cons = memory.allocate(1.into(), 2.into()).unwrap();
+        let output = Output::new(&memory, cons.into());
+
+        assert_eq!(output.to_cons(), Ok(cons));
+        assert_eq!(output.to_number(), Err(Error::NumberExpected));
+        assert_eq!(output.car().unwrap().to_number(), Ok(1));
+        assert_eq!(output.cdr().unwrap().to_number(), Ok(2));
+    }
+}
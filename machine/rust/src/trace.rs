@@ -0,0 +1,217 @@
+use crate::{Error, RunError, Value, config::TRACE_LENGTH, cons::Tag, instruction::Instruction};
+use core::{
+    error,
+    fmt::{self, Debug, Display, Formatter},
+};
+
+/// A location of an executed instruction.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Location<V> {
+    index: usize,
+    tag: Tag,
+    operand: V,
+}
+
+impl<V: Value> Location<V> {
+    pub(crate) const fn new(index: usize, tag: Tag, operand: V) -> Self {
+        Self {
+            index,
+            tag,
+            operand,
+        }
+    }
+
+    /// Returns a heap index of an instruction.
+    ///
+    /// The index is stale after copying garbage collection on a
+    /// [`Semispace`](crate::Semispace) heap as it moves the instruction.
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns an opcode.
+    pub const fn opcode(&self) -> Tag {
+        Instruction::opcode(self.tag)
+    }
+
+    /// Returns a name of an instruction if its opcode is known.
+    pub const fn name(&self) -> Option<&'static str> {
+        if let Some(instruction) = Instruction::from_tag(self.tag) {
+            Some(instruction.name())
+        } else {
+            None
+        }
+    }
+
+    /// Returns a register address.
+    pub const fn register(&self) -> usize {
+        Instruction::register(self.tag)
+    }
+
+    /// Returns an operand.
+    pub const fn operand(&self) -> V {
+        self.operand
+    }
+}
+
+/// A trace of recently executed instructions.
+///
+/// It keeps a fixed number of the latest locations and drops older ones.
+#[derive(Clone, Debug)]
+pub struct Trace<V> {
+    locations: [Location<V>; TRACE_LENGTH],
+    start: usize,
+    length: usize,
+}
+
+impl<V: Value> Trace<V> {
+    pub(crate) fn new() -> Self {
+        Self {
+            locations: [Default::default(); _],
+            start: 0,
+            length: 0,
+        }
+    }
+
+    pub(crate) const fn push(&mut self, location: Location<V>) {
+        if self.length < TRACE_LENGTH {
+            self.locations[(self.start + self.length) % TRACE_LENGTH] = location;
+            self.length += 1;
+        } else {
+            self.locations[self.start] = location;
+            self.start = (self.start + 1) % TRACE_LENGTH;
+        }
+    }
+
+    pub(crate) const fn clear(&mut self) {
+        self.start = 0;
+        self.length = 0;
+    }
+
+    /// Returns a location of the latest instruction.
+    pub const fn last(&self) -> Option<&Location<V>> {
+        if self.length == 0 {
+            None
+        } else {
+            Some(&self.locations[(self.start + self.length - 1) % TRACE_LENGTH])
+        }
+    }
+
+    /// Returns locations from the oldest to the latest.
+    pub fn iter(&self) -> impl Iterator<Item = &Location<V>> {
+        (0..self.length).map(|index| &self.locations[(self.start + index) % TRACE_LENGTH])
+    }
+}
+
+/// A run error with a trace of instructions executed until the error.
+#[derive(Clone, Debug)]
+pub struct TracedError<E, V> {
+    error: RunError<E>,
+    trace: Trace<V>,
+}
+
+impl<E, V: Value> TracedError<E, V> {
+    pub(crate) const fn new(error: RunError<E>, trace: Trace<V>) -> Self {
+        Self { error, trace }
+    }
+
+    /// Returns an error.
+    pub const fn error(&self) -> &RunError<E> {
+        &self.error
+    }
+
+    /// Returns a location of the last instruction that started executing.
+    ///
+    /// It returns `None` if the error happened before execution.
+    pub const fn location(&self) -> Option<&Location<V>> {
+        self.trace.last()
+    }
+
+    /// Returns a trace.
+    pub const fn trace(&self) -> &Trace<V> {
+        &self.trace
+    }
+}
+
+impl<E: error::Error + 'static, V: Debug> error::Error for TracedError<E, V> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl<E, V> Display for TracedError<E, V> {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.error, formatter)
+    }
+}
+
+impl<E, V: Value> From<Error> for TracedError<E, V> {
+    fn from(error: Error) -> Self {
+        Self::new(error.into(), Trace::new())
+    }
+}
+
+impl<E, V> From<TracedError<E, V>> for RunError<E> {
+    fn from(error: TracedError<E, V>) -> Self {
+        error.error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Value64;
+    use alloc::vec::Vec;
+    use pretty_assertions::assert_eq;
+
+    fn location(index: usize) -> Location<Value64> {
+        Location::new(index, Instruction::Cons.tag(0b11), 42.into())
+    }
+
+    #[test]
+    fn decode_location() {
+        let location = Location::<Value64>::new(7, Instruction::Move.tag(0b101), 42.into());
+
+        assert_eq!(location.index(), 7);
+        assert_eq!(location.opcode(), Instruction::Move as _);
+        assert_eq!(location.name(), Some("move"));
+        assert_eq!(location.register(), 0b101);
+        assert_eq!(location.operand(), 42.into());
+        assert_eq!(Location::<Value64>::new(0, 0b111, 0.into()).name(), None);
+    }
+
+    #[test]
+    fn push_locations() {
+        let mut trace = Trace::new();
+
+        assert_eq!(trace.last(), None);
+
+        trace.push(location(1));
+        trace.push(location(2));
+
+        assert_eq!(trace.last(), Some(&location(2)));
+        assert_eq!(
+            trace.iter().copied().collect::<Vec<_>>(),
+            [location(1), location(2)]
+        );
+    }
+
+    #[test]
+    fn drop_old_locations() {
+        let mut trace = Trace::new();
+
+        for index in 0..TRACE_LENGTH + 2 {
+            trace.push(location(index));
+        }
+
+        assert_eq!(
+            trace.iter().map(Location::index).collect::<Vec<_>>(),
+            (2..TRACE_LENGTH + 2).collect::<Vec<_>>()
+        );
+        assert_eq!(trace.last(), Some(&location(TRACE_LENGTH + 1)));
+
+        trace.clear();
+
+        assert_eq!(trace.iter().count(), 0);
+    }
+}
@@ -59,6 +59,14 @@ pub trait Value:
     }
 }
 
+// Converts an integer into a number if it fits in a value with tag bits.
+pub(crate) fn number_from_i64<V: Value>(integer: i64) -> Option<V::Number> {
+    let bits = V::Number::BITS - 2;
+
+    (bits >= i64::BITS as usize || (-(1 << (bits - 1))..1 << (bits - 1)).contains(&integer))
+        .then(|| V::Number::from_i64(integer))
+}
+
 /// A 16-bit value.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Value16(u16);
@@ -0,0 +1,424 @@
+mod violation;
+
+pub use self::violation::{Violation, ViolationKind};
+use crate::{Cons, Heap, Integer, Memory, Value, instruction::Instruction};
+
+/// A verifier.
+///
+/// It iterates over violations in a code list without running it. Register
+/// paths of instructions and `move` operands are resolved against the root
+/// structure of a memory at verification. So nested registers need to be
+/// built before a program is verified.
+#[derive(Debug)]
+pub struct Verifier<'a, V, H> {
+    memory: &'a Memory<V, H>,
+    code: Option<V>,
+    tortoise: V,
+    position: usize,
+    check: usize,
+}
+
+impl<'a, V: Value, H: Heap<V>> Verifier<'a, V, H> {
+    /// Creates a verifier of a code list.
+    pub const fn new(memory: &'a Memory<V, H>, code: V) -> Self {
+        Self {
+            memory,
+            code: Some(code),
+            tortoise: code,
+            position: 0,
+            check: 0,
+        }
+    }
+
+    fn verify_register(&self, address: usize) -> Option<ViolationKind> {
+        self.memory
+            .resolve(address)
+            .is_err()
+            .then_some(ViolationKind::InvalidRegister(address))
+    }
+
+    const fn violate(&mut self, kind: ViolationKind) -> Option<Violation> {
+        self.code = None;
+
+        Some(Violation::new(self.position, kind))
+    }
+}
+
+impl<V: Value, H: Heap<V>> Iterator for Verifier<'_, V, H> {
+    type Item = Violation;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Ok(cons) = self.code?.to_cons() else {
+                return self.violate(ViolationKind::CodeListExpected);
+            };
+
+            let (Ok(operand), Ok(next)) = (
+                self.memory.get(cons.index()),
+                self.memory.get(cons.index() + 1),
+            ) else {
+                return self.violate(ViolationKind::InvalidMemoryAccess);
+            };
+            let Ok(next) = next.to_cons() else {
+                self.code = None;
+                return None;
+            };
+            let tag = next.tag();
+            let instruction = Instruction::from_tag(tag);
+
+            let kind = match self.check {
+                0 => instruction
+                    .is_none()
+                    .then_some(ViolationKind::UnknownInstruction(tag)),
+                1 if instruction.is_some_and(Instruction::has_register) => {
+                    self.verify_register(Instruction::register(tag))
+                }
+                2 if instruction == Some(Instruction::Move) => operand
+                    .to_number()
+                    .map_or(Some(ViolationKind::NumberExpected), |number| {
+                        self.verify_register(number.to_usize())
+                    }),
+                2 if instruction == Some(Instruction::Operate) => operand
+                    .to_number()
+                    .is_err()
+                    .then_some(ViolationKind::NumberExpected),
+                2 if instruction.is_some_and(Instruction::has_branch) => operand
+                    .to_cons()
+                    .is_err()
+                    .then_some(ViolationKind::CodeListExpected),
+                1 | 2 => None,
+                _ => {
+                    self.code = Some(next.into());
+                    self.position += 1;
+                    self.check = 0;
+
+                    // Detect a cycle by Floyd's algorithm with a tortoise
+                    // moving at a half speed of the code list.
+                    if self.position.is_multiple_of(2) {
+                        let Ok(tortoise) = self.memory.get(Cons::from(self.tortoise).index() + 1)
+                        else {
+                            return self.violate(ViolationKind::InvalidMemoryAccess);
+                        };
+
+                        self.tortoise = tortoise;
+                    }
+
+                    if Cons::from(self.tortoise).index() == next.index() {
+                        return self.violate(ViolationKind::CyclicCodeList);
+                    }
+
+                    continue;
+                }
+            };
+
+            self.check += 1;
+
+            if let Some(kind) = kind {
+                return Some(Violation::new(self.position, kind));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Error, Machine, Value64, VoidOperationSet, assemble};
+    use alloc::vec::Vec;
+    use core::error::Error as _;
+    use pretty_assertions::assert_eq;
+
+    const HEAP_SIZE: usize = 1 << 8;
+
+    type TestMachine = Machine<Value64, [Value64; HEAP_SIZE], 0b10, VoidOperationSet>;
+
+    fn create_machine(source: &str) -> TestMachine {
+        let mut machine = Machine::new([Default::default(); _], VoidOperationSet::new()).unwrap();
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>(source, &mut bytecode).unwrap();
+        machine.initialize(bytecode).unwrap();
+
+        machine
+    }
+
+    fn verify(source: &str) -> Vec<Violation> {
+        create_machine(source).verify().unwrap().collect()
+    }
+
+    fn create_memory() -> Memory<Value64, [Value64; HEAP_SIZE]> {
+        Memory::new([Default::default(); _]).unwrap()
+    }
+
+    #[test]
+    fn verify_empty_program() {
+        assert_eq!(verify(""), []);
+    }
+
+    #[test]
+    fn verify_valid_program() {
+        assert_eq!(verify("cons 0b11 1\nmove 0b11 0b10"), []);
+    }
+
+    #[test]
+    fn verify_branch() {
+        assert_eq!(verify("if 0b11 1\ncons 0b11 1"), []);
+    }
+
+    #[test]
+    fn verify_call() {
+        assert_eq!(verify("call 0b11 1\nreturn 0b11\nreturn 0b11"), []);
+    }
+
+    #[test]
+    fn report_invalid_continuation_register() {
+        assert_eq!(
+            verify("call 0b0 0\nreturn 0b1"),
+            [
+                Violation::new(0, ViolationKind::InvalidRegister(0b0)),
+                Violation::new(1, ViolationKind::InvalidRegister(0b1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn report_non_code_list_branch() {
+        let mut memory = create_memory();
+        let cons = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(cons.into());
+        let cons = memory
+            .allocate(
+                Default::default(),
+                cons.set_tag(Instruction::If.tag(0b11)).into(),
+            )
+            .unwrap();
+        memory.set_root(cons.into());
+
+        assert_eq!(
+            Verifier::new(&memory, cons.into()).collect::<Vec<_>>(),
+            [Violation::new(0, ViolationKind::CodeListExpected)]
+        );
+    }
+
+    #[test]
+    fn report_invalid_register() {
+        assert_eq!(
+            verify("cons 0b1 1"),
+            [Violation::new(0, ViolationKind::InvalidRegister(0b1))]
+        );
+    }
+
+    #[test]
+    fn verify_nested_register() {
+        let mut machine = create_machine("cons 0b1011 1\nmove 0b101 0b1111");
+
+        machine.scope(|scope| {
+            let null = scope.number(0).unwrap();
+            let registers = scope.cons(&null, &null).unwrap();
+            let registers = scope.cons(&null, &registers).unwrap();
+
+            scope.set_register(0b11, &registers).unwrap();
+        });
+
+        assert_eq!(machine.verify().unwrap().collect::<Vec<_>>(), []);
+    }
+
+    #[test]
+    fn report_register_outside_root() {
+        assert_eq!(
+            verify("cons 0b11 1\ncons 0b1011 1\nmove 0b11 0b10111"),
+            [
+                Violation::new(1, ViolationKind::InvalidRegister(0b1011)),
+                Violation::new(2, ViolationKind::InvalidRegister(0b10111)),
+            ]
+        );
+    }
+
+    #[test]
+    fn report_invalid_move_operand() {
+        assert_eq!(
+            verify("move 0b11 0b1"),
+            [Violation::new(0, ViolationKind::InvalidRegister(0b1))]
+        );
+    }
+
+    #[test]
+    fn report_every_violation() {
+        assert_eq!(
+            verify("cons 1 0\nmove 0b11 0\ncons 0b11 0\nmove 0 1"),
+            [
+                Violation::new(0, ViolationKind::InvalidRegister(1)),
+                Violation::new(1, ViolationKind::InvalidRegister(0)),
+                Violation::new(3, ViolationKind::InvalidRegister(0)),
+                Violation::new(3, ViolationKind::InvalidRegister(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn report_non_number_move_operand() {
+        let mut memory = create_memory();
+        let cons = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(cons.into());
+        let cons = memory
+            .allocate(
+                cons.into(),
+                cons.set_tag(Instruction::Move.tag(0b11)).into(),
+            )
+            .unwrap();
+        memory.set_root(cons.into());
+
+        assert_eq!(
+            Verifier::new(&memory, cons.into()).collect::<Vec<_>>(),
+            [Violation::new(0, ViolationKind::NumberExpected)]
+        );
+    }
+
+    #[test]
+    fn report_unknown_instruction() {
+        let mut memory = create_memory();
+        let cons = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(cons.into());
+        let cons = memory
+            .allocate(Default::default(), cons.set_tag(0b1111).into())
+            .unwrap();
+        memory.set_root(cons.into());
+
+        assert_eq!(
+            Verifier::new(&memory, cons.into()).collect::<Vec<_>>(),
+            [Violation::new(0, ViolationKind::UnknownInstruction(0b1111))]
+        );
+    }
+
+    #[test]
+    fn report_non_number_operate_operand() {
+        let mut memory = create_memory();
+        let cons = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(cons.into());
+        let cons = memory
+            .allocate(
+                cons.into(),
+                cons.set_tag(Instruction::Operate.tag(0)).into(),
+            )
+            .unwrap();
+        memory.set_root(cons.into());
+
+        assert_eq!(
+            Verifier::new(&memory, cons.into()).collect::<Vec<_>>(),
+            [Violation::new(0, ViolationKind::NumberExpected)]
+        );
+    }
+
+    #[test]
+    fn verify_operate() {
+        assert_eq!(verify("operate 0\noperate 42"), []);
+    }
+
+    #[test]
+    fn report_code_list_expected() {
+        let memory = create_memory();
+
+        assert_eq!(
+            Verifier::new(&memory, 42.into()).collect::<Vec<_>>(),
+            [Violation::new(0, ViolationKind::CodeListExpected)]
+        );
+    }
+
+    #[test]
+    fn report_cyclic_code_list() {
+        let mut memory = create_memory();
+        let cons = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(cons.into());
+        memory
+            .set(
+                cons.index() + 1,
+                cons.set_tag(Instruction::Cons.tag(0b10)).into(),
+            )
+            .unwrap();
+
+        assert_eq!(
+            Verifier::new(&memory, cons.into()).last(),
+            Some(Violation::new(1, ViolationKind::CyclicCodeList))
+        );
+    }
+
+    #[test]
+    fn report_long_cyclic_code_list() {
+        const LENGTH: usize = 5;
+
+        let mut memory = create_memory();
+        let head = memory
+            .allocate(Default::default(), Default::default())
+            .unwrap();
+        memory.set_root(head.into());
+        let mut cons = head;
+
+        for _ in 1..LENGTH {
+            cons = memory
+                .allocate(
+                    Default::default(),
+                    cons.set_tag(Instruction::Cons.tag(0b10)).into(),
+                )
+                .unwrap();
+            memory.set_root(cons.into());
+        }
+
+        memory
+            .set(
+                head.index() + 1,
+                cons.set_tag(Instruction::Cons.tag(0b10)).into(),
+            )
+            .unwrap();
+
+        let violations = Verifier::new(&memory, cons.into()).collect::<Vec<_>>();
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].kind(), ViolationKind::CyclicCodeList);
+        assert!(violations[0].position() <= 2 * LENGTH);
+    }
+
+    #[test]
+    fn report_invalid_memory_access() {
+        let memory = create_memory();
+
+        assert_eq!(
+            Verifier::new(&memory, crate::Cons::new(HEAP_SIZE).into()).collect::<Vec<_>>(),
+            [Violation::new(0, ViolationKind::InvalidMemoryAccess)]
+        );
+    }
+
+    #[test]
+    fn reject_invalid_program() {
+        let mut machine = Machine::<Value64, [Value64; HEAP_SIZE], 0b10, _>::new(
+            [Default::default(); _],
+            VoidOperationSet::new(),
+        )
+        .unwrap();
+        let mut bytecode = Vec::new();
+
+        assemble::<Value64, 0b10>("cons 0b11 1\ncons 0b1 2", &mut bytecode).unwrap();
+
+        assert_eq!(
+            machine
+                .run(bytecode)
+                .unwrap_err()
+                .source()
+                .unwrap()
+                .downcast_ref(),
+            Some(&Error::InvalidProgram(Violation::new(
+                1,
+                ViolationKind::InvalidRegister(1)
+            )))
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use crate::cons::Tag;
+use core::fmt::{self, Display, Formatter};
+
+/// A violation in a program.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Violation {
+    position: usize,
+    kind: ViolationKind,
+}
+
+impl Violation {
+    pub(crate) const fn new(position: usize, kind: ViolationKind) -> Self {
+        Self { position, kind }
+    }
+
+    /// Returns a position of an instruction in a code list.
+    pub const fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns a violation kind.
+    pub const fn kind(&self) -> ViolationKind {
+        self.kind
+    }
+}
+
+impl Display for Violation {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        write!(formatter, "instruction {}: {}", self.position, self.kind)
+    }
+}
+
+/// A violation kind.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ViolationKind {
+    /// Code list expected.
+    CodeListExpected,
+    /// Cyclic code list.
+    CyclicCodeList,
+    /// Invalid memory access.
+    InvalidMemoryAccess,
+    /// Invalid register.
+    InvalidRegister(usize),
+    /// Number expected.
+    NumberExpected,
+    /// Unknown instruction.
+    UnknownInstruction(Tag),
+}
+
+impl Display for ViolationKind {
+    fn fmt(&self, formatter: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::CodeListExpected => write!(formatter, "code list expected"),
+            Self::CyclicCodeList => write!(formatter, "cyclic code list"),
+            Self::InvalidMemoryAccess => write!(formatter, "invalid memory access"),
+            Self::InvalidRegister(address) => write!(formatter, "invalid register {address:#b}"),
+            Self::NumberExpected => write!(formatter, "number expected"),
+            Self::UnknownInstruction(tag) => write!(formatter, "unknown instruction {tag:#x}"),
+        }
+    }
+}